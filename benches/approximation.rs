@@ -0,0 +1,46 @@
+//! Mediant bisection vs continued fraction convergents.
+//!
+//! Run with `cargo bench`; Criterion writes its HTML report to
+//! `target/criterion/report/index.html`. Before timing, the number of
+//! iterations each algorithm needs is printed for every target, since the
+//! step count is what decides which algorithm should be the default.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use mediant_rs::{farey, farey_convergents, farey_convergents_with_steps, farey_with_steps};
+
+const TARGETS: [(&str, f64); 3] = [
+    ("rational 1/3", 1.0 / 3.0),
+    ("algebraic sqrt2", std::f64::consts::SQRT_2),
+    ("transcendental pi", std::f64::consts::PI),
+];
+
+fn print_iteration_counts() {
+    println!("{:<20} {:>10} {:>12}", "target", "mediant", "convergents");
+    for (name, x) in TARGETS {
+        let mut mediant_steps = 0u64;
+        farey_with_steps(x, |_, _, _| mediant_steps += 1).expect("farey converges");
+        let mut convergent_steps = 0u64;
+        farey_convergents_with_steps(x, |_| convergent_steps += 1).expect("convergents converge");
+        println!("{name:<20} {mediant_steps:>10} {convergent_steps:>12}");
+    }
+}
+
+fn approximation(c: &mut Criterion) {
+    print_iteration_counts();
+
+    let mut group = c.benchmark_group("approximation");
+    for (name, x) in TARGETS {
+        group.bench_with_input(BenchmarkId::new("mediant", name), &x, |b, &x| {
+            b.iter(|| farey(black_box(x)))
+        });
+        group.bench_with_input(BenchmarkId::new("convergents", name), &x, |b, &x| {
+            b.iter(|| farey_convergents(black_box(x)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, approximation);
+criterion_main!(benches);
@@ -0,0 +1,28 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    #[cfg(feature = "c-ffi")]
+    generate_header();
+}
+
+/// Writes the C header for the `ffi` module to `$OUT_DIR/mediant.h`, leaving
+/// the source tree untouched; `tests/ffi_test.sh` picks it up from there.
+#[cfg(feature = "c-ffi")]
+fn generate_header() {
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR is set by cargo");
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    let config = cbindgen::Config {
+        language: cbindgen::Language::C,
+        include_guard: Some("MEDIANT_H".to_string()),
+        ..Default::default()
+    };
+
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("unable to generate C bindings")
+        .write_to_file(format!("{out_dir}/mediant.h"));
+}
@@ -0,0 +1,57 @@
+//! Mediant bisections vs continued fraction partial quotients.
+//!
+//! Run with `cargo run --example benchmark_cf`. For each target the table
+//! shows how many bisections `farey` takes, the sum of the partial
+//! quotients of its result after a0, and the first convergent of the target
+//! with a denominator above 10^6:
+//!
+//! ```text
+//! target               bisections     cf sum   first q > 10^6
+//! pi                          341        342          1360120
+//! e                            63         64          4996032
+//! sqrt2                        41         42          1136689
+//! phi                          38         39          1346269
+//! ln2                          48         49          1206321
+//! 355/113                      22         23                -
+//! 0.827                        18         19                -
+//! 1/1000.5                   1001       1002                -
+//! 17/23                         8          9                -
+//! ```
+//!
+//! The search starts between a0 and a0 + 1, at the node [a0; 2], and each
+//! bisection after that is one turn down the Stern-Brocot tree. A result
+//! [a0; a1, ..., an] sits a1 + ... + an - 1 turns below [a0; 1] = a0 + 1, so
+//! the bisection count is always the partial quotient sum less one; the
+//! example checks this for every target and panics if it fails.
+
+use mediant_rs::{farey_with_steps, ContinuedFraction};
+
+const TARGETS: [(&str, f64); 9] = [
+    ("pi", std::f64::consts::PI),
+    ("e", std::f64::consts::E),
+    ("sqrt2", std::f64::consts::SQRT_2),
+    ("phi", 1.618_033_988_749_895),
+    ("ln2", std::f64::consts::LN_2),
+    ("355/113", 355.0 / 113.0),
+    ("0.827", 0.827),
+    ("1/1000.5", 1.0 / 1000.5),
+    ("17/23", 17.0 / 23.0),
+];
+
+fn main() {
+    println!("{:<20} {:>10} {:>10} {:>16}", "target", "bisections", "cf sum", "first q > 10^6");
+    for (name, x) in TARGETS {
+        let mut bisections = 0u64;
+        let result = farey_with_steps(x, |_, _, _| bisections += 1).expect("farey converges");
+        let cf_sum: u64 = ContinuedFraction::from_fraction(result).terms()[1..].iter().sum();
+        assert_eq!(bisections, cf_sum - 1, "{name}: bisections disagree with the partial quotients");
+
+        let first_large = ContinuedFraction::from_f64(x)
+            .expect("targets are valid")
+            .convergents()
+            .map(|convergent| convergent.denominator())
+            .find(|&q| q > 1_000_000)
+            .map_or_else(|| "-".to_string(), |q| q.to_string());
+        println!("{name:<20} {bisections:>10} {cf_sum:>10} {first_large:>16}");
+    }
+}
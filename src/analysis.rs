@@ -0,0 +1,482 @@
+//! Tools for judging how good an approximation is.
+
+use crate::farey::Bisection;
+use crate::{
+    farey, farey_bounded, farey_convergents_with_steps, farey_with_steps,
+    ContinuedFraction, Fraction, FractionError,
+};
+
+/// A fraction produced by [`farey`] together with its quality measures.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ApproximationResult {
+    /// The approximated real number.
+    pub target: f64,
+    /// The fraction found by [`farey`].
+    pub fraction: Fraction,
+    /// |target - fraction|.
+    pub error: f64,
+    /// True if no fraction with a smaller or equal denominator is closer to
+    /// the target, checked against [`farey_bounded`]. Every convergent is, and
+    /// so are some semiconvergents.
+    pub is_best_for_denominator: bool,
+    /// The fraction's index k as the convergent p_k/q_k of the target; see
+    /// [`continued_fraction_depth`].
+    pub convergent_index: Option<usize>,
+}
+
+/// How well a given fraction approximates a real number; see [`farey_verify`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerificationResult {
+    /// The fraction in lowest terms is a convergent of the target.
+    pub is_convergent: bool,
+    /// No fraction with a smaller or equal denominator is closer.
+    pub is_best_for_denom: bool,
+    /// |target - fraction|.
+    pub error: f64,
+    /// Length of the fraction's path from 1/1 in the Stern-Brocot tree.
+    pub stern_brocot_depth: u64,
+    /// The continued fraction of the fraction itself.
+    pub continued_fraction: ContinuedFraction,
+}
+
+/// The two approximation algorithms run side by side; see [`compare_algorithms`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComparisonResult {
+    /// Bisections the [`farey`] search took.
+    pub mediant_steps: u64,
+    /// Convergents [`farey_convergents`](crate::farey_convergents) computed.
+    pub convergent_depth: usize,
+    /// The fraction found by [`farey`].
+    pub mediant_result: Fraction,
+    /// The fraction found by [`farey_convergents`](crate::farey_convergents).
+    pub convergent_result: Fraction,
+    /// The two results have the same value.
+    pub agree: bool,
+}
+
+/// How quickly the [`farey`] search closes in on its target.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvergenceStats {
+    /// |target - mediant_k| for each bisection k = 1, 2, ...
+    pub errors: Vec<f64>,
+    /// `errors[k] / errors[k-1]`; one shorter than `errors`.
+    pub ratios: Vec<f64>,
+}
+
+/// Records the error of each mediant the [`farey`] search visits, for at most
+/// `max_iter` bisections.
+///
+/// The ratios characterize how approximable the target is. The error only
+/// drops sharply when the search turns, so long runs of turns in one direction
+/// (large partial quotients) show up as ratios near 1 punctuated by sharp
+/// drops, while the golden ratio, which turns at every step, shrinks by a
+/// steady factor of about 1/φ². Per bisection that is fast; it is per
+/// denominator size that the golden ratio is the slowest, by Hurwitz's
+/// theorem (see [`lagrange_constant_approx`]).
+pub fn convergence_quality(real: f64, max_iter: usize) -> Result<ConvergenceStats, FractionError> {
+    let errors = Bisection::new(real)?
+        .take(max_iter)
+        .map(|step| step.map(|step| (real - step.mediant.value()).abs()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let ratios = errors.windows(2).map(|pair| pair[1] / pair[0]).collect();
+    Ok(ConvergenceStats { errors, ratios })
+}
+
+/// Counts the bisections the [`farey`] search needs to come within `epsilon`
+/// of `real`.
+///
+/// The count is the depth of the first mediant that close in the Stern-Brocot
+/// tree, which is the sum of the partial quotients used so far. A large
+/// partial quotient is a long run of turns the same way, so π ([3; 7, 15, 1,
+/// 292, ...]) takes more steps to reach 1e-6 than the golden ratio, whose
+/// partial quotients are all 1, even though the golden ratio is the harder
+/// number to approximate by denominator size.
+///
+/// The search itself stops within `f64::EPSILON`, so a smaller `epsilon` is
+/// raised to that and counts the whole search.
+///
+/// Returns `InvalidInput` unless `epsilon` is positive.
+pub fn bisection_count(real: f64, epsilon: f64) -> Result<u64, FractionError> {
+    if epsilon.is_nan() || epsilon <= 0.0 {
+        return Err(FractionError::InvalidInput);
+    }
+    let epsilon = epsilon.max(f64::EPSILON);
+    let mut count = 0;
+    for step in Bisection::new(real)? {
+        let step = step?;
+        count += 1;
+        if (real - step.mediant.value()).abs() < epsilon {
+            break;
+        }
+    }
+    Ok(count)
+}
+
+/// Runs both [`farey`] and [`farey_convergents`](crate::farey_convergents) on
+/// `real` and reports their results and step counts.
+///
+/// Both find the first node on the Stern-Brocot path within `f64::EPSILON`
+/// of the target, so `agree` always holds: the bisection visits every node,
+/// while the convergents skip whole runs and bisect the run they stop in,
+/// which matters when a semiconvergent is close enough first (344609/106487251
+/// for 0.0032361526545559415, ahead of the convergent 459329/141936753). The
+/// fractions are compared by value, since `farey` leaves integers
+/// unreduced. The convergents take far fewer steps.
+pub fn compare_algorithms(real: f64) -> Result<ComparisonResult, FractionError> {
+    let mut mediant_steps = 0;
+    let mediant_result = farey_with_steps(real, |_, _, _| mediant_steps += 1)?;
+    let mut convergent_depth = 0;
+    let convergent_result = farey_convergents_with_steps(real, |_| convergent_depth += 1)?;
+    Ok(ComparisonResult {
+        mediant_steps,
+        convergent_depth,
+        mediant_result,
+        convergent_result,
+        agree: Fraction::cross_product(mediant_result, convergent_result) == 0,
+    })
+}
+
+/// Returns the first `n` convergents of `real`, each with its signed error
+/// p/q - real.
+///
+/// The convergents alternate around the target, starting at or below it, so the
+/// errors alternate in sign while shrinking in absolute value, and each is
+/// the best approximation for its denominator. Fewer than `n` are returned if
+/// the expansion ends within `f64` precision.
+pub fn convergent_errors(real: f64, n: usize) -> Result<Vec<(Fraction, f64)>, FractionError> {
+    let cf = ContinuedFraction::from_f64(real)?;
+    let real = crate::validate_input(real)?;
+    Ok(cf
+        .convergents()
+        .take(n)
+        .map(|convergent| (convergent, convergent.value() - real))
+        .collect())
+}
+
+/// Returns true if `frac` is one of the continued fraction convergents of `real`.
+///
+/// By the best rational approximation theorem, p/q is a convergent of x iff
+/// |qx - p| is smaller than for any fraction with denominator below q, so a
+/// convergent is certified optimal for its denominator. The comparison is on
+/// the literal components: an unreduced 6/4 is never a convergent, even
+/// though 3/2 may be.
+pub fn farey_is_convergent(real: f64, frac: Fraction) -> bool {
+    continued_fraction_depth(frac, real).is_some()
+}
+
+/// Returns k if `frac` is the convergent p_k/q_k of `real`, counting from
+/// p_0/q_0 = a0/1, or `None` if it is not a convergent.
+///
+/// 355/113 is p_3/q_3 of π, the fourth convergent after 3/1, 22/7 and
+/// 333/106. As with [`farey_is_convergent`], the comparison is on the literal
+/// components, so an unreduced 22/8 is never a convergent.
+pub fn continued_fraction_depth(frac: Fraction, real: f64) -> Option<usize> {
+    let (mut k, mut depth) = (0, None);
+    // Convergent denominators are never zero, so this cannot fail
+    let _ = farey_convergents_with_steps(real, |convergent| {
+        if depth.is_none() && *convergent == frac {
+            depth = Some(k);
+        }
+        k += 1;
+    });
+    depth
+}
+
+/// Returns a certified bound on |real - frac| for a convergent `frac` of
+/// `real`.
+///
+/// For the convergent p_k/q_k, |x - p_k/q_k| < 1/(q_k q_{k+1}). When p_k/q_k
+/// is the deepest convergent computed (the expansion stops within `f64`
+/// precision), q_{k+1} is unknown, but it is at least q_k + q_{k-1} since
+/// a_{k+1} >= 1, so that is used instead and the bound is a little looser.
+///
+/// Returns `InvalidInput` if `frac` is not a convergent of `real`, compared
+/// on the literal components as in [`continued_fraction_depth`].
+pub fn farey_error_bound(real: f64, frac: Fraction) -> Result<f64, FractionError> {
+    let cf = ContinuedFraction::from_f64(real)?;
+    let denominators: Vec<u64> = cf.convergents().map(|c| c.denominator()).collect();
+    let k = cf
+        .convergents()
+        .position(|convergent| convergent == frac)
+        .ok_or(FractionError::InvalidInput)?;
+    let q = denominators[k] as f64;
+    let next = match denominators.get(k + 1) {
+        Some(&next) => next as f64,
+        // q_{k-1} = 0 before the first convergent
+        None => q + k.checked_sub(1).map_or(0.0, |j| denominators[j] as f64),
+    };
+    Ok(1.0 / (q * next))
+}
+
+/// Approximates `real` with [`farey`] and reports whether the result is certified
+/// to be a convergent.
+///
+/// The result is reduced for the check, since [`farey`] leaves integers
+/// unreduced (6/2 for 3.0); the fraction is returned as [`farey`] found it.
+pub fn farey_certified(real: f64) -> Result<(Fraction, bool), FractionError> {
+    let fraction = farey(real)?;
+    Ok((fraction, continued_fraction_depth(fraction.reduce(), real).is_some()))
+}
+
+/// Approximates `real` with [`farey`] and collects an [`ApproximationResult`].
+///
+/// As in [`farey_certified`], the convergent check is on the reduced result,
+/// and so is the denominator the best-approximation check is bounded by.
+pub fn farey_approximation(real: f64) -> Result<ApproximationResult, FractionError> {
+    let fraction = farey(real)?;
+    let reduced = fraction.reduce();
+    let error = (real - fraction.value()).abs();
+    let best = farey_bounded(real, reduced.denominator())?;
+    Ok(ApproximationResult {
+        target: real,
+        fraction,
+        error,
+        is_best_for_denominator: error <= (real - best.value()).abs(),
+        convergent_index: continued_fraction_depth(reduced, real),
+    })
+}
+
+/// Judges `frac` as an approximation of `real`, for a fraction that came from
+/// somewhere other than [`farey`].
+///
+/// The fraction is reduced first. Being a convergent is the stronger
+/// property; some semiconvergents are also best for their denominator.
+/// Returns `InvalidInput` for a zero fraction, which has no Stern-Brocot
+/// path.
+pub fn farey_verify(frac: Fraction, real: f64) -> Result<VerificationResult, FractionError> {
+    let real = crate::validate_input(real)?;
+    let frac = frac.reduce();
+    let error = (real - frac.value()).abs();
+    let best = farey_bounded(real, frac.denominator())?;
+    Ok(VerificationResult {
+        is_convergent: farey_is_convergent(real, frac),
+        is_best_for_denom: error <= (real - best.value()).abs(),
+        error,
+        stern_brocot_depth: frac.stern_brocot_path_length()?,
+        continued_fraction: ContinuedFraction::from_fraction(frac),
+    })
+}
+
+/// Estimates the Lagrange constant of `x` from its first `depth` convergents.
+///
+/// The Lagrange constant is L(x) = limsup 1/(q^2 |x - p/q|) over the
+/// convergents p/q of x: the largest c such that |x - p/q| < 1/(c q^2) has
+/// infinitely many solutions. Hurwitz's theorem says L(x) >= √5 for every
+/// irrational x, with equality for the golden ratio and the numbers
+/// equivalent to it; Markov showed the values below 3 form a discrete
+/// sequence √5, √8, √221/5, ... Numbers with finite L(x), i.e. bounded
+/// partial quotients, are called badly approximable.
+///
+/// The limsup is estimated as the maximum over the second half of the first
+/// `depth` convergents. Convergents that equal `x` to `f64` precision are
+/// skipped, which in practice limits useful depths to denominators below 1e8.
+/// Returns `InvalidInput` if no convergent is left, as for integers.
+pub fn lagrange_constant_approx(x: f64, depth: u32) -> Result<f64, FractionError> {
+    let cf = ContinuedFraction::from_f64(x)?;
+    let values: Vec<f64> = cf
+        .convergents()
+        .take(depth as usize)
+        .filter_map(|convergent| {
+            let error = (x - convergent.value()).abs();
+            let q = convergent.denominator() as f64;
+            (error > 0.0).then(|| 1.0 / (q * q * error))
+        })
+        .collect();
+    if values.is_empty() {
+        return Err(FractionError::InvalidInput);
+    }
+    Ok(values[values.len() / 2..].iter().copied().fold(0.0, f64::max))
+}
+
+/// Returns the record-setting approximations of `real` with denominator at
+/// most `max_denominator`, each with its error.
+///
+/// Tries every denominator q in increasing order with the nearest numerator
+/// round(q * real), and keeps a fraction only if it is strictly closer than
+/// everything before it. These are the best approximations in the sense of
+/// |x - p/q|: every convergent (bar a0/1 when a0 + 1 is nearer), plus the
+/// semiconvergents that beat the previous convergent. Runs in
+/// O(max_denominator) time, stopping early once the error reaches zero or
+/// q * real no longer fits in `u64`.
+pub fn farey_all_closer(real: f64, max_denominator: u64) -> Result<Vec<(Fraction, f64)>, FractionError> {
+    let real = crate::validate_input(real)?;
+    let mut records: Vec<(Fraction, f64)> = Vec::new();
+    for q in 1..=max_denominator {
+        let p = (q as f64 * real).round();
+        if p >= u64::MAX as f64 {
+            break;
+        }
+        let frac = Fraction::new(p as u64, q)?;
+        let error = (real - frac.value()).abs();
+        if records.last().is_none_or(|&(_, best)| error < best) {
+            records.push((frac, error));
+            if error == 0.0 {
+                break;
+            }
+        }
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn frac(numerator: u64, denominator: u64) -> Fraction {
+        Fraction::new(numerator, denominator).unwrap()
+    }
+
+    #[test]
+    fn integer_results_are_certified() {
+        for x in [0.0, 1.0, 3.0, 42.0] {
+            let (fraction, certified) = farey_certified(x).unwrap();
+            assert!(certified, "{x} gave {fraction:?}");
+            let approximation = farey_approximation(x).unwrap();
+            assert!(approximation.is_best_for_denominator);
+            assert_eq!(approximation.convergent_index, Some(0));
+        }
+    }
+
+    #[test]
+    fn semiconvergent_results_can_be_best_for_their_denominator() {
+        let x = 0.0032361526545559415;
+        let approximation = farey_approximation(x).unwrap();
+        assert_eq!(approximation.fraction, frac(344609, 106487251));
+        assert_eq!(approximation.convergent_index, None);
+        assert!(approximation.is_best_for_denominator);
+        for x in [std::f64::consts::PI, 0.827, 1.0 / 3.0] {
+            let approximation = farey_approximation(x).unwrap();
+            assert!(approximation.convergent_index.is_some() && approximation.is_best_for_denominator, "{x}");
+        }
+    }
+
+    #[test]
+    fn certified_convergents() {
+        for x in [std::f64::consts::PI, 0.827, 0.5, 1.0 / 3.0, 22.0 / 7.0] {
+            assert!(farey_certified(x).unwrap().1, "{x}");
+        }
+        assert!(farey_is_convergent(std::f64::consts::PI, frac(22, 7)));
+        assert!(!farey_is_convergent(std::f64::consts::PI, frac(13, 4)));
+    }
+
+    #[test]
+    fn all_closer_contains_the_convergents() {
+        for x in [
+            std::f64::consts::PI,
+            std::f64::consts::E,
+            0.827,
+            std::f64::consts::SQRT_2,
+            17.0 / 23.0,
+        ] {
+            let records = farey_all_closer(x, 10_000).unwrap();
+            assert!(records.windows(2).all(|pair| {
+                pair[0].0.denominator() < pair[1].0.denominator() && pair[1].1 < pair[0].1
+            }));
+            // a0/1 is beaten by a0 + 1 when a1 = 1, as 2/1 is by 3/1 for e
+            for convergent in Fraction::convergents_up_to_denom(x, 10_000)
+                .unwrap()
+                .into_iter()
+                .skip(1)
+            {
+                let error = (x - convergent.value()).abs();
+                assert!(
+                    records.contains(&(convergent, error)),
+                    "{convergent:?} missing for {x}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn all_closer_of_pi() {
+        let pi = std::f64::consts::PI;
+        let records: Vec<_> = farey_all_closer(pi, 113).unwrap().into_iter().map(|(f, _)| f).collect();
+        // 13/4, 16/5 and 19/6 are semiconvergents between 3/1 and 22/7
+        assert_eq!(records[..5], [frac(3, 1), frac(13, 4), frac(16, 5), frac(19, 6), frac(22, 7)]);
+        assert_eq!(records.last(), Some(&frac(355, 113)));
+        assert_eq!(farey_all_closer(0.5, 10).unwrap(), [(frac(1, 1), 0.5), (frac(1, 2), 0.0)]);
+        assert_eq!(farey_all_closer(f64::NAN, 10), Err(FractionError::InvalidInput));
+    }
+
+    #[test]
+    fn verify_deep_fractions() {
+        let deep = frac(1, 1 << 40);
+        let report = farey_verify(deep, deep.value()).unwrap();
+        assert_eq!(report.stern_brocot_depth, (1 << 40) - 1);
+        assert!(report.is_convergent && report.is_best_for_denom);
+        let report = farey_verify(frac(22, 7), std::f64::consts::PI).unwrap();
+        assert_eq!(report.stern_brocot_depth, 9);
+        assert!(report.is_convergent);
+        assert_eq!(farey_verify(frac(0, 1), 0.5).err(), Some(FractionError::InvalidInput));
+    }
+
+    #[test]
+    fn bisection_count_raises_tiny_epsilons() {
+        let pi = std::f64::consts::PI;
+        let mut full = 0;
+        crate::farey_with_steps(pi, |_, _, _| full += 1).unwrap();
+        assert_eq!(bisection_count(pi, f64::EPSILON), Ok(full));
+        assert_eq!(bisection_count(pi, 1e-300), Ok(full));
+        // 7/2, 10/3, 13/4, 16/5, 19/6, 22/7: the sixth mediant is within 1e-2
+        assert_eq!(bisection_count(pi, 1e-2), Ok(6));
+        assert_eq!(bisection_count(22.0 / 7.0, 1e-9), Ok(bisection_count(pi, 1e-2).unwrap()));
+        assert_eq!(bisection_count(pi, 0.0), Err(FractionError::InvalidInput));
+        assert_eq!(bisection_count(pi, f64::NAN), Err(FractionError::InvalidInput));
+    }
+
+    #[test]
+    fn convergent_errors_shrink_and_alternate() {
+        for x in [std::f64::consts::PI, std::f64::consts::E, std::f64::consts::SQRT_2, 0.827] {
+            let errors = convergent_errors(x, 8).unwrap();
+            assert!(errors.windows(2).all(|pair| pair[1].1.abs() < pair[0].1.abs()), "{x}: {errors:?}");
+            for (k, &(convergent, error)) in errors.iter().enumerate() {
+                assert_eq!(error, convergent.value() - x);
+                // Even convergents lie below x and odd ones above, until one hits it
+                assert!(error == 0.0 || (error < 0.0) == (k % 2 == 0), "{x}: {errors:?}");
+            }
+        }
+        let pi = convergent_errors(std::f64::consts::PI, 4).unwrap();
+        let fractions: Vec<_> = pi.iter().map(|&(f, _)| f).collect();
+        assert_eq!(fractions, [frac(3, 1), frac(22, 7), frac(333, 106), frac(355, 113)]);
+        assert_eq!(convergent_errors(0.5, 10).unwrap(), [(frac(0, 1), -0.5), (frac(1, 2), 0.0)]);
+        assert_eq!(convergent_errors(-0.5, 10), Err(FractionError::InvalidInput));
+    }
+
+    #[test]
+    fn continued_fraction_depth_of_pi() {
+        let pi = std::f64::consts::PI;
+        assert_eq!(continued_fraction_depth(frac(3, 1), pi), Some(0));
+        assert_eq!(continued_fraction_depth(frac(22, 7), pi), Some(1));
+        assert_eq!(continued_fraction_depth(frac(355, 113), pi), Some(3));
+        assert_eq!(continued_fraction_depth(frac(104348, 33215), pi), Some(5));
+        // A semiconvergent, and an unreduced form of a convergent
+        assert_eq!(continued_fraction_depth(frac(13, 4), pi), None);
+        assert_eq!(continued_fraction_depth(frac(22, 8), pi), None);
+        assert_eq!(continued_fraction_depth(frac(44, 14), pi), None);
+        assert_eq!(continued_fraction_depth(frac(3, 1), 3.0), Some(0));
+        assert_eq!(continued_fraction_depth(frac(1, 2), -0.5), None);
+    }
+
+    #[test]
+    fn compare_algorithms_on_semiconvergent_results() {
+        let report = compare_algorithms(0.0032361526545559415).unwrap();
+        assert_eq!(report.mediant_result, frac(344609, 106487251));
+        assert!(report.agree && report.convergent_result == report.mediant_result);
+        assert!(report.convergent_depth < report.mediant_steps as usize);
+        let report = compare_algorithms(3.0).unwrap();
+        assert_eq!((report.mediant_result, report.convergent_result), (frac(6, 2), frac(3, 1)));
+        assert!(report.agree);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(1000))]
+
+        #[test]
+        fn algorithms_always_agree(x in 0.0..1000.0f64) {
+            let report = compare_algorithms(x).unwrap();
+            prop_assert!(report.agree, "{report:?}");
+        }
+    }
+}
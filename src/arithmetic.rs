@@ -0,0 +1,168 @@
+//! Exact arithmetic on fractions.
+//!
+//! Each operator returns `Result<Fraction, FractionError>`: `Overflow` if a
+//! component leaves the `u64` range (including a negative difference) and
+//! `DivByZero` when dividing by zero. Products are computed in `u128`, so
+//! intermediate values never overflow. Results are not reduced when they fit
+//! as computed; a result that only fits in lowest terms is reduced rather
+//! than rejected. Call [`Fraction::reduce`] or use
+//! [`ReducedFraction`](crate::ReducedFraction) for reduced results throughout.
+
+use std::ops::{Add, Div, Mul, Sub};
+
+use crate::number_theory::gcd_u128;
+use crate::{Fraction, FractionError};
+
+/// a/b + c/d = (a(d/g) + c(b/g)) / (bd/g), where g = gcd(b, d)
+///
+/// Dividing out g first keeps the sum within `u128`, which ad + cb can
+/// exceed when both denominators are near `u64::MAX`.
+impl Add for Fraction {
+    type Output = Result<Fraction, FractionError>;
+
+    fn add(self, rhs: Fraction) -> Self::Output {
+        let (a, b, c, d) = components(self, rhs);
+        let g = gcd_u128(b, d);
+        match (a * (d / g)).checked_add(c * (b / g)) {
+            Some(numerator) => narrow(Some(numerator), Some(b / g * d)),
+            // For operands in lowest terms the sum would not fit in u64 anyway
+            None if (self, rhs) != (self.reduce(), rhs.reduce()) => self.reduce() + rhs.reduce(),
+            None => Err(FractionError::Overflow),
+        }
+    }
+}
+
+/// a/b - c/d = (ad - cb) / bd
+impl Sub for Fraction {
+    type Output = Result<Fraction, FractionError>;
+
+    fn sub(self, rhs: Fraction) -> Self::Output {
+        let (a, b, c, d) = components(self, rhs);
+        narrow((a * d).checked_sub(c * b), Some(b * d))
+    }
+}
+
+/// a/b * c/d = ac / bd
+impl Mul for Fraction {
+    type Output = Result<Fraction, FractionError>;
+
+    fn mul(self, rhs: Fraction) -> Self::Output {
+        let (a, b, c, d) = components(self, rhs);
+        narrow(Some(a * c), Some(b * d))
+    }
+}
+
+/// (a/b) / (c/d) = ad / bc
+impl Div for Fraction {
+    type Output = Result<Fraction, FractionError>;
+
+    fn div(self, rhs: Fraction) -> Self::Output {
+        let (a, b, c, d) = components(self, rhs);
+        narrow(Some(a * d), Some(b * c))
+    }
+}
+
+/// The components widened to `u128`, where the product of any two fits.
+fn components(lhs: Fraction, rhs: Fraction) -> (u128, u128, u128, u128) {
+    (
+        lhs.numerator() as u128,
+        lhs.denominator() as u128,
+        rhs.numerator() as u128,
+        rhs.denominator() as u128,
+    )
+}
+
+/// Narrows a `u128` result to a [`Fraction`], reducing it if that is what it
+/// takes to fit. `None` components (a `u128` sum overflowed or a difference
+/// went negative) are `Overflow`.
+fn narrow(numerator: Option<u128>, denominator: Option<u128>) -> Result<Fraction, FractionError> {
+    let (numerator, denominator) = numerator.zip(denominator).ok_or(FractionError::Overflow)?;
+    let fits = |n: u128, d: u128| u64::try_from(n).ok().zip(u64::try_from(d).ok());
+    let (numerator, denominator) = match fits(numerator, denominator) {
+        Some(parts) => parts,
+        None => {
+            let divisor = gcd_u128(numerator, denominator).max(1);
+            fits(numerator / divisor, denominator / divisor).ok_or(FractionError::Overflow)?
+        }
+    };
+    Fraction::new(numerator, denominator)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn frac(numerator: u64, denominator: u64) -> Fraction {
+        Fraction::new(numerator, denominator).unwrap()
+    }
+
+    /// True if `result` is `Ok` with the value numerator / denominator.
+    fn has_value(result: Result<Fraction, FractionError>, numerator: u128, denominator: u128) -> bool {
+        result.is_ok_and(|r| r.numerator() as u128 * denominator == numerator * r.denominator() as u128)
+    }
+
+    #[test]
+    fn sums_near_u64_max() {
+        let one = frac(u64::MAX, u64::MAX);
+        assert_eq!(one + one, Ok(frac(2, 1)));
+        assert_eq!(one + frac(u64::MAX - 1, u64::MAX - 1), Ok(frac(2, 1)));
+        assert_eq!(frac(1, u64::MAX) + frac(1, u64::MAX), Ok(frac(2, u64::MAX)));
+        assert_eq!(frac(1, u64::MAX) + frac(1, u64::MAX - 1), Err(FractionError::Overflow));
+        assert_eq!(frac(u64::MAX, 1) + frac(1, 1), Err(FractionError::Overflow));
+    }
+
+    proptest! {
+        #[test]
+        fn small_operands_are_exact(
+            a in 0..1u64 << 31, b in 1..1u64 << 31, c in 0..1u64 << 31, d in 1..1u64 << 31,
+        ) {
+            // Components below 2^31 keep every unreduced result within u64
+            let (x, y) = (frac(a, b), frac(c, d));
+            let (a, b, c, d) = (a as u128, b as u128, c as u128, d as u128);
+            prop_assert!(has_value(x + y, a * d + c * b, b * d));
+            prop_assert!(has_value(x * y, a * c, b * d));
+            if c > 0 {
+                prop_assert!(has_value(x / y, a * d, b * c));
+            } else {
+                prop_assert_eq!(x / y, Err(FractionError::DivByZero));
+            }
+            if a * d >= c * b {
+                prop_assert!(has_value(x - y, a * d - c * b, b * d));
+            } else {
+                prop_assert_eq!(x - y, Err(FractionError::Overflow));
+            }
+        }
+
+        #[test]
+        fn large_operands_round_trip(a in 0..u64::MAX, b in 1..u64::MAX, c in 0..u64::MAX, d in 1..u64::MAX) {
+            let (x, y) = (frac(a, b), frac(c, d));
+            prop_assert_eq!(x + y, y + x);
+            prop_assert_eq!(x * y, y * x);
+            if let Ok(sum) = x + y {
+                if let Ok(back) = sum - y {
+                    prop_assert_eq!(Fraction::cross_product(back, x), 0);
+                }
+            }
+            if let (Ok(product), true) = (x * y, c > 0) {
+                if let Ok(back) = product / y {
+                    prop_assert_eq!(Fraction::cross_product(back, x), 0);
+                }
+            }
+        }
+
+        #[test]
+        fn equal_values_add_like_doubling(p in 0..u64::MAX, q in 1..u64::MAX, k in 1..1000u64) {
+            // p/q and its multiple kp/kq have the same value
+            let x = frac(p, q);
+            let scaled = p.checked_mul(k).zip(q.checked_mul(k)).map(|(p, q)| frac(p, q)).unwrap_or(x);
+            if let Ok(sum) = x + scaled {
+                prop_assert_eq!(Fraction::cross_product(sum, (x * frac(2, 1)).unwrap()), 0);
+            }
+            let reduced = x.reduce();
+            let reduced_sum = (reduced + reduced).map(|sum| sum.reduce());
+            prop_assert_eq!((x + scaled).map(|sum| sum.reduce()), reduced_sum);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::{Fraction, FractionError};
+
+/// A running [`Fraction`] result that supports the `*=`-style operators.
+///
+/// The operators on `Fraction` return `Result`, which leaves nowhere for
+/// `frac += delta` to put an error. `CheckedFraction` holds the `Result`
+/// instead: once an operation fails, the error sticks and later operations
+/// leave it unchanged, so a whole loop can be checked once at the end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckedFraction(Result<Fraction, FractionError>);
+
+impl CheckedFraction {
+    /// Starts from `frac`.
+    pub fn new(frac: Fraction) -> Self {
+        Self(Ok(frac))
+    }
+
+    /// Returns the current value, or the first error any operation hit.
+    pub fn get(&self) -> Result<Fraction, FractionError> {
+        self.0
+    }
+
+    /// Returns true if an operation has failed.
+    pub fn is_err(&self) -> bool {
+        self.0.is_err()
+    }
+}
+
+impl From<Fraction> for CheckedFraction {
+    fn from(frac: Fraction) -> Self {
+        Self::new(frac)
+    }
+}
+
+impl From<Result<Fraction, FractionError>> for CheckedFraction {
+    fn from(result: Result<Fraction, FractionError>) -> Self {
+        Self(result)
+    }
+}
+
+impl From<CheckedFraction> for Result<Fraction, FractionError> {
+    fn from(checked: CheckedFraction) -> Self {
+        checked.0
+    }
+}
+
+macro_rules! checked_assign_op {
+    ($trait:ident, $method:ident, $op:tt) => {
+        impl $trait<Fraction> for CheckedFraction {
+            fn $method(&mut self, rhs: Fraction) {
+                self.0 = self.0.and_then(|lhs| lhs $op rhs);
+            }
+        }
+
+        impl $trait for CheckedFraction {
+            fn $method(&mut self, rhs: CheckedFraction) {
+                self.0 = self.0.and_then(|lhs| rhs.0.and_then(|rhs| lhs $op rhs));
+            }
+        }
+    };
+}
+
+checked_assign_op!(AddAssign, add_assign, +);
+checked_assign_op!(SubAssign, sub_assign, -);
+checked_assign_op!(MulAssign, mul_assign, *);
+checked_assign_op!(DivAssign, div_assign, /);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frac(numerator: u64, denominator: u64) -> Fraction {
+        Fraction::new(numerator, denominator).unwrap()
+    }
+
+    #[test]
+    fn operators_accumulate() {
+        let mut total = CheckedFraction::new(frac(1, 2));
+        total += frac(1, 3);
+        total -= frac(1, 6);
+        total *= frac(3, 1);
+        total /= frac(4, 1);
+        assert_eq!(total.get().map(|f| f.reduce()), Ok(frac(1, 2)));
+        assert!(!total.is_err());
+    }
+
+    #[test]
+    fn failures_carry_forward() {
+        let mut total = CheckedFraction::new(frac(1, 2));
+        total -= frac(1, 1);
+        assert_eq!(total.get(), Err(FractionError::Overflow));
+        total += frac(1, 1);
+        total *= frac(2, 1);
+        total /= frac(1, 2);
+        assert_eq!(total.get(), Err(FractionError::Overflow));
+
+        // The first error sticks, even when a later operation would fail differently
+        let mut total = CheckedFraction::new(frac(1, 2));
+        total /= frac(0, 1);
+        assert_eq!(total.get(), Err(FractionError::DivByZero));
+        total -= frac(1, 1);
+        assert_eq!(total.get(), Err(FractionError::DivByZero));
+
+        let mut total = CheckedFraction::new(frac(u64::MAX, 1));
+        total *= frac(2, 1);
+        total += frac(0, 1);
+        assert!(total.is_err());
+        assert_eq!(Result::from(total), Err(FractionError::Overflow));
+    }
+
+    #[test]
+    fn failed_operands_carry_forward() {
+        let failed = CheckedFraction::from(frac(1, 1) / frac(0, 1));
+        let ops = [AddAssign::add_assign, SubAssign::sub_assign, MulAssign::mul_assign, DivAssign::div_assign];
+        for op in ops {
+            let mut total = CheckedFraction::new(frac(1, 2));
+            op(&mut total, failed);
+            assert_eq!(total.get(), Err(FractionError::DivByZero));
+        }
+        let mut total = CheckedFraction::new(frac(1, 2));
+        total += CheckedFraction::new(frac(1, 2));
+        assert_eq!(total.get().map(|f| f.reduce()), Ok(frac(1, 1)));
+    }
+}
@@ -0,0 +1,218 @@
+use std::fmt;
+
+use crate::{farey_convergents_with_steps, Fraction, FractionError};
+
+/// A finite simple continued fraction [a0; a1, a2, ...].
+///
+/// Represents a0 + 1/(a1 + 1/(a2 + ...)). The partial quotients a1, a2, ...
+/// are all at least 1; only a0 may be zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContinuedFraction {
+    terms: Vec<u64>,
+}
+
+/// Output notations for [`ContinuedFraction::display_as`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContinuedFractionFormat {
+    /// `[3; 7, 15, 1]`, also used by `Display`.
+    Standard,
+    /// `3 + \cfrac{1}{7 + \cfrac{1}{15 + \cfrac{1}{1}}}`
+    LaTeX,
+    /// `3;7,15,1`
+    Compact,
+    /// `3 + 1/(7 + 1/(15 + 1/1))`
+    Verbose,
+}
+
+impl ContinuedFraction {
+    /// Builds a continued fraction from its terms [a0, a1, a2, ...].
+    ///
+    /// Returns `InvalidInput` if `terms` is empty or any partial quotient after
+    /// a0 is zero.
+    pub fn new(terms: Vec<u64>) -> Result<Self, FractionError> {
+        if terms.is_empty() || terms[1..].contains(&0) {
+            return Err(FractionError::InvalidInput);
+        }
+        Ok(Self { terms })
+    }
+
+    /// Expands a fraction with the Euclidean algorithm.
+    ///
+    /// Unreduced inputs give the same terms as their reduced form.
+    pub fn from_fraction(frac: Fraction) -> Self {
+        let (mut p, mut q) = (frac.numerator(), frac.denominator());
+        let mut terms = Vec::new();
+        while q != 0 {
+            terms.push(p / q);
+            (p, q) = (q, p % q);
+        }
+        Self { terms }
+    }
+
+    /// Expands a real number, as far as `f64` precision allows.
+    ///
+    /// The terms are those of the last convergent
+    /// [`farey_convergents`](crate::farey_convergents) reaches, so `x` is
+    /// within epsilon of the value of the result unless the next convergent
+    /// would overflow `u64`.
+    pub fn from_f64(x: f64) -> Result<Self, FractionError> {
+        let mut last = None;
+        farey_convergents_with_steps(x, |convergent| last = Some(*convergent))?;
+        Ok(Self::from_fraction(last.expect("a0/1 is always reached")))
+    }
+
+    /// Expands a decimal string such as `"3.14159265358979323846"` exactly.
+    ///
+    /// The string is read as the rational digits / 10^k with integer
+    /// arithmetic, skipping the rounding of an `f64` conversion, so every term
+    /// reflects the digits as written. Up to 38 significant digits fit.
+    ///
+    /// Returns `InvalidInput` unless the string is an optional `+`, digits and
+    /// at most one `.`, and `Overflow` if it has too many digits or a term
+    /// exceeds `u64`.
+    pub fn from_decimal_str(s: &str) -> Result<Self, FractionError> {
+        let s = s.trim();
+        let s = s.strip_prefix('+').unwrap_or(s);
+        let (integer, fractional) = s.split_once('.').unwrap_or((s, ""));
+        // Trailing zeros only inflate the denominator
+        let fractional = fractional.trim_end_matches('0');
+        let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if (integer.is_empty() && fractional.is_empty()) || !is_digits(integer) || !is_digits(fractional) {
+            return Err(FractionError::InvalidInput);
+        }
+
+        let mut numerator = 0u128;
+        for digit in integer.bytes().chain(fractional.bytes()) {
+            numerator = numerator
+                .checked_mul(10)
+                .and_then(|n| n.checked_add((digit - b'0') as u128))
+                .ok_or(FractionError::Overflow)?;
+        }
+        let mut denominator = u32::try_from(fractional.len())
+            .ok()
+            .and_then(|k| 10u128.checked_pow(k))
+            .ok_or(FractionError::Overflow)?;
+
+        let mut terms = Vec::new();
+        while denominator != 0 {
+            terms.push(u64::try_from(numerator / denominator).map_err(|_| FractionError::Overflow)?);
+            (numerator, denominator) = (denominator, numerator % denominator);
+        }
+        Ok(Self { terms })
+    }
+
+    /// Returns the terms [a0, a1, a2, ...].
+    pub fn terms(&self) -> &[u64] {
+        &self.terms
+    }
+
+    /// Returns the convergents [a0], [a0; a1], [a0; a1, a2], ... in order.
+    ///
+    /// Uses the recurrence p_k = a_k p_{k-1} + p_{k-2}, q_k = a_k q_{k-1} + q_{k-2}.
+    /// Stops early if a convergent would overflow `u64`.
+    pub fn convergents(&self) -> impl Iterator<Item = Fraction> + '_ {
+        convergents_of(self.terms.iter().copied())
+    }
+
+    /// Renders the nested `\cfrac` form for LaTeX (requires amsmath).
+    pub fn to_latex_cfrac(&self) -> String {
+        self.nested(|head, tail| format!("{head} + \\cfrac{{1}}{{{tail}}}"))
+    }
+
+    /// Returns a wrapper that formats this continued fraction in `format`.
+    pub fn display_as(&self, format: ContinuedFractionFormat) -> ContinuedFractionDisplay<'_> {
+        ContinuedFractionDisplay { cf: self, format }
+    }
+
+    /// Folds the terms from the innermost outwards, combining each term with
+    /// the already-rendered tail.
+    fn nested(&self, combine: impl Fn(u64, &str) -> String) -> String {
+        let (last, rest) = self.terms.split_last().expect("terms are never empty");
+        rest.iter()
+            .rev()
+            .fold(last.to_string(), |tail, &head| combine(head, &tail))
+    }
+}
+
+/// Convergents of the continued fraction with the given terms; see
+/// [`ContinuedFraction::convergents`].
+pub(crate) fn convergents_of(terms: impl Iterator<Item = u64>) -> impl Iterator<Item = Fraction> {
+    // Seed with p_{-2}/q_{-2} = 0/1 and p_{-1}/q_{-1} = 1/0
+    let mut state = ((0u64, 1u64), (1u64, 0u64));
+    terms.map_while(move |a| {
+        let (before, last) = state;
+        let p = a.checked_mul(last.0)?.checked_add(before.0)?;
+        let q = a.checked_mul(last.1)?.checked_add(before.1)?;
+        state = (last, (p, q));
+        Fraction::new(p, q).ok()
+    })
+}
+
+/// Formats a [`ContinuedFraction`] in a chosen notation; see
+/// [`ContinuedFraction::display_as`].
+#[derive(Debug, Clone, Copy)]
+pub struct ContinuedFractionDisplay<'a> {
+    cf: &'a ContinuedFraction,
+    format: ContinuedFractionFormat,
+}
+
+impl fmt::Display for ContinuedFractionDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (a0, rest) = self.cf.terms.split_first().expect("terms are never empty");
+        let join = |sep: &str| {
+            rest.iter().map(u64::to_string).collect::<Vec<_>>().join(sep)
+        };
+
+        match self.format {
+            ContinuedFractionFormat::Standard if rest.is_empty() => write!(f, "[{a0}]"),
+            ContinuedFractionFormat::Standard => write!(f, "[{a0}; {}]", join(", ")),
+            ContinuedFractionFormat::Compact if rest.is_empty() => write!(f, "{a0}"),
+            ContinuedFractionFormat::Compact => write!(f, "{a0};{}", join(",")),
+            ContinuedFractionFormat::LaTeX => f.write_str(&self.cf.to_latex_cfrac()),
+            ContinuedFractionFormat::Verbose => {
+                let verbose = self.cf.nested(|head, tail| {
+                    if tail.contains(' ') {
+                        format!("{head} + 1/({tail})")
+                    } else {
+                        format!("{head} + 1/{tail}")
+                    }
+                });
+                f.write_str(&verbose)
+            }
+        }
+    }
+}
+
+/// Formats as `[a0; a1, a2, ...]`.
+impl fmt::Display for ContinuedFraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.display_as(ContinuedFractionFormat::Standard).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_formats() {
+        let cf = ContinuedFraction::new(vec![3, 7, 15, 1]).unwrap();
+        let show = |format| cf.display_as(format).to_string();
+        assert_eq!(show(ContinuedFractionFormat::Standard), "[3; 7, 15, 1]");
+        assert_eq!(
+            show(ContinuedFractionFormat::LaTeX),
+            "3 + \\cfrac{1}{7 + \\cfrac{1}{15 + \\cfrac{1}{1}}}"
+        );
+        assert_eq!(show(ContinuedFractionFormat::Compact), "3;7,15,1");
+        assert_eq!(show(ContinuedFractionFormat::Verbose), "3 + 1/(7 + 1/(15 + 1/1))");
+        assert_eq!(cf.to_string(), "[3; 7, 15, 1]");
+    }
+
+    #[test]
+    fn display_formats_single_term() {
+        let cf = ContinuedFraction::new(vec![4]).unwrap();
+        assert_eq!(cf.to_string(), "[4]");
+        assert_eq!(cf.display_as(ContinuedFractionFormat::Compact).to_string(), "4");
+        assert_eq!(cf.display_as(ContinuedFractionFormat::Verbose).to_string(), "4");
+    }
+}
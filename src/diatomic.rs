@@ -0,0 +1,117 @@
+//! Stern's diatomic sequence and the Calkin-Wilf enumeration of the rationals.
+//!
+//! s(0) = 0, s(1) = 1, s(2n) = s(n), s(2n+1) = s(n) + s(n+1). The ratios
+//! s(n)/s(n+1) for n = 1, 2, 3, ... run through the Calkin-Wilf tree in
+//! breadth-first order, 1/1, 1/2, 2/1, 1/3, 3/2, 2/3, 3/1, ..., so every
+//! positive rational appears exactly once.
+
+use crate::{Fraction, FractionError};
+
+/// Returns s(n), Stern's diatomic sequence (also known as fusc).
+///
+/// Reads the bits of n from the lowest, keeping s(n) = a s(m) + b s(m+1) for
+/// the remaining prefix m, so it runs in O(log n).
+pub fn stern_diatomic(n: u64) -> u64 {
+    let (mut a, mut b) = (1u64, 0u64);
+    let mut n = n;
+    while n > 0 {
+        if n & 1 == 1 {
+            b += a;
+        } else {
+            a += b;
+        }
+        n >>= 1;
+    }
+    b
+}
+
+/// Returns s(n)/s(n+1), the n-th term of the Calkin-Wilf sequence (0/1 for n = 0).
+///
+/// Returns `Overflow` for `u64::MAX`, whose successor does not fit.
+pub fn stern_diatomic_fraction(n: u64) -> Result<Fraction, FractionError> {
+    let next = n.checked_add(1).ok_or(FractionError::Overflow)?;
+    Fraction::new(stern_diatomic(n), stern_diatomic(next))
+}
+
+/// Returns the smallest n with s(n)/s(n+1) equal to `frac` in lowest terms.
+///
+/// Climbs the Calkin-Wilf tree from `frac` to the root 1/1: p/q is the left
+/// child of p/(q-p) when p < q and the right child of (p-q)/q otherwise, and
+/// a node's index is twice its parent's, plus one for a right child. Runs of
+/// the same step are taken with one division.
+///
+/// Returns `Overflow` if the index does not fit in `u64` (the fraction lies
+/// deeper than level 63).
+pub fn stern_diatomic_index(frac: Fraction) -> Result<u64, FractionError> {
+    let frac = frac.reduce();
+    let (mut p, mut q) = (frac.numerator(), frac.denominator());
+    if p == 0 {
+        return Ok(0);
+    }
+
+    // Bits of the index below the leading 1, collected from the lowest
+    let (mut low_bits, mut depth) = (0u64, 0u32);
+    while p != q {
+        // Stop the run at 1/1 rather than stepping past it
+        let (run, bit) = if p < q {
+            let run = if q % p == 0 { q / p - 1 } else { q / p };
+            q -= run * p;
+            (run, 0)
+        } else {
+            let run = if p % q == 0 { p / q - 1 } else { p / q };
+            p -= run * q;
+            (run, 1)
+        };
+        if run >= 64 || depth + run as u32 > 63 {
+            return Err(FractionError::Overflow);
+        }
+        if bit == 1 {
+            low_bits |= ((1u64 << run) - 1) << depth;
+        }
+        depth += run as u32;
+    }
+    Ok(1 << depth | low_bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frac(numerator: u64, denominator: u64) -> Fraction {
+        Fraction::new(numerator, denominator).unwrap()
+    }
+
+    #[test]
+    fn known_values() {
+        let values: Vec<u64> = (0..=16).map(stern_diatomic).collect();
+        assert_eq!(values, [0, 1, 1, 2, 1, 3, 2, 3, 1, 4, 3, 5, 2, 5, 3, 4, 1]);
+        assert_eq!(stern_diatomic(1 << 63), 1);
+        assert_eq!(stern_diatomic_fraction(0), Ok(frac(0, 1)));
+        let calkin_wilf: Vec<Fraction> = (1..=7).map(|n| stern_diatomic_fraction(n).unwrap()).collect();
+        let expected = [frac(1, 1), frac(1, 2), frac(2, 1), frac(1, 3), frac(3, 2), frac(2, 3), frac(3, 1)];
+        assert_eq!(calkin_wilf, expected);
+    }
+
+    #[test]
+    fn index_inverts_fraction() {
+        for n in (0..1 << 14).chain([u64::MAX - 1, 1 << 63, (1 << 63) - 1, 0xdead_beef_cafe]) {
+            assert_eq!(stern_diatomic_index(stern_diatomic_fraction(n).unwrap()), Ok(n), "{n}");
+        }
+    }
+
+    #[test]
+    fn unreduced_fractions_index_as_their_reduced_form() {
+        assert_eq!(stern_diatomic_index(frac(4, 6)), Ok(6));
+        assert_eq!(stern_diatomic_index(frac(0, 5)), Ok(0));
+    }
+
+    #[test]
+    fn overflow_past_level_63() {
+        // 1/64 ends the left spine at level 63; 1/65 is one level deeper
+        assert_eq!(stern_diatomic_index(frac(1, 64)), Ok(1 << 63));
+        assert_eq!(stern_diatomic_index(frac(1, 65)), Err(FractionError::Overflow));
+        assert_eq!(stern_diatomic_index(frac(65, 1)), Err(FractionError::Overflow));
+        assert_eq!(stern_diatomic_index(frac(u64::MAX, 1)), Err(FractionError::Overflow));
+        assert_eq!(stern_diatomic_fraction(u64::MAX), Err(FractionError::Overflow));
+    }
+}
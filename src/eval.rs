@@ -0,0 +1,230 @@
+//! A small calculator for fraction expressions.
+//!
+//! Grammar, from lowest to highest precedence:
+//!
+//! ```text
+//! expr   = term (("+" | "-") term)*
+//! term   = power (("*" | "/") power)*
+//! power  = atom ("^" power)?
+//! atom   = integer | "(" expr ")" | "mediant(" expr "," expr ")" | "reduce(" expr ")"
+//! ```
+//!
+//! `^` is right-associative, the other operators left-associative.
+
+use crate::{Fraction, FractionError};
+
+/// How deeply parentheses, calls and `^` chains may nest before the
+/// expression is rejected rather than overflowing the stack.
+const MAX_DEPTH: usize = 256;
+
+/// Evaluates an arithmetic expression over fractions, e.g. `"1/3 + 2/5"` is
+/// 11/15 and `"mediant(1/3, 1/4)"` is 2/7.
+///
+/// Operators behave like the ones on [`Fraction`]: results are not reduced,
+/// so `"6/4"` stays 6/4 unless wrapped in `reduce(...)`, and `mediant` sees
+/// its arguments as written. Exponents must be whole numbers.
+///
+/// Returns `InvalidInput` for syntax errors, fractional exponents and
+/// nesting more than 256 levels deep, and otherwise whatever error the arithmetic produces (`Overflow` for a
+/// negative difference, as with [`Fraction`]'s `-`).
+pub fn eval_rational(expr: &str) -> Result<Fraction, FractionError> {
+    let mut parser = Parser { input: expr.as_bytes(), pos: 0, depth: 0 };
+    let value = parser.expr()?;
+    if parser.peek().is_some() {
+        return Err(FractionError::InvalidInput);
+    }
+    Ok(value)
+}
+
+/// Recursive descent over the grammar in the module docs, one method per rule.
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+    /// Number of `power` calls in progress; every nested rule passes through one.
+    depth: usize,
+}
+
+impl Parser<'_> {
+    /// Returns the next non-whitespace byte without consuming it.
+    fn peek(&mut self) -> Option<u8> {
+        while self.input.get(self.pos).is_some_and(u8::is_ascii_whitespace) {
+            self.pos += 1;
+        }
+        self.input.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), FractionError> {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(FractionError::InvalidInput)
+        }
+    }
+
+    fn expr(&mut self) -> Result<Fraction, FractionError> {
+        let mut value = self.term()?;
+        loop {
+            match self.peek() {
+                Some(b'+') => {
+                    self.pos += 1;
+                    value = (value + self.term()?)?;
+                }
+                Some(b'-') => {
+                    self.pos += 1;
+                    value = (value - self.term()?)?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<Fraction, FractionError> {
+        let mut value = self.power()?;
+        loop {
+            match self.peek() {
+                Some(b'*') => {
+                    self.pos += 1;
+                    value = (value * self.power()?)?;
+                }
+                Some(b'/') => {
+                    self.pos += 1;
+                    value = (value / self.power()?)?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn power(&mut self) -> Result<Fraction, FractionError> {
+        if self.depth == MAX_DEPTH {
+            return Err(FractionError::InvalidInput);
+        }
+        self.depth += 1;
+        let value = self.exponentiation();
+        self.depth -= 1;
+        value
+    }
+
+    fn exponentiation(&mut self) -> Result<Fraction, FractionError> {
+        let base = self.atom()?;
+        if self.peek() != Some(b'^') {
+            return Ok(base);
+        }
+        self.pos += 1;
+        let exponent = self.power()?;
+        if exponent.numerator() % exponent.denominator() != 0 {
+            return Err(FractionError::InvalidInput);
+        }
+        let exponent = u32::try_from(exponent.numerator() / exponent.denominator())
+            .map_err(|_| FractionError::Overflow)?;
+        Fraction::from_checked(
+            base.numerator().checked_pow(exponent),
+            base.denominator().checked_pow(exponent),
+        )
+    }
+
+    fn atom(&mut self) -> Result<Fraction, FractionError> {
+        match self.peek() {
+            Some(b'(') => {
+                self.pos += 1;
+                let value = self.expr()?;
+                self.expect(b')')?;
+                Ok(value)
+            }
+            Some(b'0'..=b'9') => self.integer(),
+            Some(b'a'..=b'z') => self.call(),
+            _ => Err(FractionError::InvalidInput),
+        }
+    }
+
+    fn integer(&mut self) -> Result<Fraction, FractionError> {
+        let mut value = 0u64;
+        while let Some(digit @ b'0'..=b'9') = self.input.get(self.pos).copied() {
+            self.pos += 1;
+            value = value
+                .checked_mul(10)
+                .and_then(|v| v.checked_add((digit - b'0') as u64))
+                .ok_or(FractionError::Overflow)?;
+        }
+        Fraction::new(value, 1)
+    }
+
+    fn call(&mut self) -> Result<Fraction, FractionError> {
+        let start = self.pos;
+        while self.input.get(self.pos).is_some_and(u8::is_ascii_lowercase) {
+            self.pos += 1;
+        }
+        let name = &self.input[start..self.pos];
+        self.expect(b'(')?;
+        let value = match name {
+            b"mediant" => {
+                let a = self.expr()?;
+                self.expect(b',')?;
+                a.mediant(&self.expr()?)?
+            }
+            b"reduce" => self.expr()?.reduce(),
+            _ => return Err(FractionError::InvalidInput),
+        };
+        self.expect(b')')?;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frac(numerator: u64, denominator: u64) -> Fraction {
+        Fraction::new(numerator, denominator).unwrap()
+    }
+
+    #[test]
+    fn examples() {
+        assert_eq!(eval_rational("1/3 + 2/5"), Ok(frac(11, 15)));
+        assert_eq!(eval_rational("(1/2)^3"), Ok(frac(1, 8)));
+        assert_eq!(eval_rational("mediant(1/3, 1/4)"), Ok(frac(2, 7)));
+        assert_eq!(eval_rational("reduce(6/4)"), Ok(frac(3, 2)));
+        assert_eq!(eval_rational("6/4"), Ok(frac(6, 4)));
+        assert_eq!(eval_rational("  7 "), Ok(frac(7, 1)));
+    }
+
+    #[test]
+    fn precedence_and_associativity() {
+        assert_eq!(eval_rational("1 + 2 * 3"), Ok(frac(7, 1)));
+        assert_eq!(eval_rational("(1 + 2) * 3"), Ok(frac(9, 1)));
+        assert_eq!(eval_rational("8 - 2 - 1"), Ok(frac(5, 1)));
+        assert_eq!(eval_rational("1 / 2 / 2").map(|f| f.reduce()), Ok(frac(1, 4)));
+        assert_eq!(eval_rational("2 * 3^2"), Ok(frac(18, 1)));
+        // Right-associative: 2^(3^2), not (2^3)^2
+        assert_eq!(eval_rational("2^3^2"), Ok(frac(512, 1)));
+        assert_eq!(eval_rational("(2^3)^2"), Ok(frac(64, 1)));
+    }
+
+    #[test]
+    fn errors() {
+        for bad in ["", "1 +", "(1/2", "1/2)", "1 ? 2", "mediant(1/2)", "sqrt(4)", "reduce 4"] {
+            assert_eq!(eval_rational(bad), Err(FractionError::InvalidInput), "{bad}");
+        }
+        assert_eq!(eval_rational("1/0"), Err(FractionError::DivByZero));
+        assert_eq!(eval_rational("1/(2-2)"), Err(FractionError::DivByZero));
+        assert_eq!(eval_rational("4^(1/2)"), Err(FractionError::InvalidInput));
+        assert_eq!(eval_rational("4^(4/2)"), Ok(frac(16, 1)));
+        assert_eq!(eval_rational("1 - 2"), Err(FractionError::Overflow));
+        assert_eq!(eval_rational("2^64"), Err(FractionError::Overflow));
+        assert_eq!(eval_rational("18446744073709551616"), Err(FractionError::Overflow));
+    }
+
+    #[test]
+    fn nesting_is_limited() {
+        let nested = |depth| format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+        assert_eq!(eval_rational(&nested(MAX_DEPTH - 1)), Ok(frac(1, 1)));
+        assert_eq!(eval_rational(&nested(MAX_DEPTH)), Err(FractionError::InvalidInput));
+        assert_eq!(eval_rational(&nested(20000)), Err(FractionError::InvalidInput));
+        let chain = |length| format!("1{}", "^1".repeat(length));
+        assert_eq!(eval_rational(&chain(MAX_DEPTH - 1)), Ok(frac(1, 1)));
+        assert_eq!(eval_rational(&chain(20000)), Err(FractionError::InvalidInput));
+        let calls = format!("{}1{}", "reduce(".repeat(20000), ")".repeat(20000));
+        assert_eq!(eval_rational(&calls), Err(FractionError::InvalidInput));
+    }
+}
@@ -0,0 +1,404 @@
+use std::cmp::Ordering;
+
+use crate::fraction::{Fraction, FractionError};
+use crate::{validate_input, ContinuedFraction, Mediant};
+
+/// Approximates a real number as a fraction using the Farey/mediant algorithm.
+///
+/// # Algorithm
+///
+/// 1. Start with two bounds: left = floor(x)/1 and right = ceil(x)/1
+/// 2. Compute the mediant of left and right
+/// 3. If mediant equals target (within epsilon), we're done
+/// 4. If mediant > target, it becomes the new right bound (search left half)
+/// 5. If mediant < target, it becomes the new left bound (search right half)
+/// 6. Repeat until convergence
+///
+/// This is essentially binary search over the Stern-Brocot tree, which contains
+/// all positive rationals exactly once. The mediant operation naturally traverses
+/// this tree, guaranteeing we find the best rational approximation.
+/// see: https://cp-algorithms.com/others/stern_brocot_tree_farey_sequences.html
+pub fn farey(real_number: f64) -> Result<Fraction, FractionError> {
+    farey_with_steps(real_number, |_, _, _| {})
+}
+
+/// Runs [`farey`], calling `on_step(left, mediant, right)` before each bisection.
+///
+/// The CLI uses this to print the bounds as they narrow.
+pub fn farey_with_steps<F>(real_number: f64, mut on_step: F) -> Result<Fraction, FractionError>
+where
+    F: FnMut(&Fraction, &Fraction, &Fraction),
+{
+    for step in Bisection::new(real_number)? {
+        let step = step?;
+        on_step(&step.left, &step.mediant, &step.right);
+        if step.converged {
+            return Ok(step.mediant);
+        }
+    }
+    unreachable!("bisection only ends after converging or failing")
+}
+
+/// Returns `(left, mediant, right)` after `n` bisection steps of [`farey`].
+///
+/// `n = 0` gives the starting bounds floor(x)/1 and ceil(x)/1 and their
+/// mediant. If the search converges in fewer steps, the converged step is
+/// returned. Replays the search from the start, so each call is O(n); this is
+/// meant for stepping through it interactively.
+pub fn farey_step_n(real_number: f64, n: usize) -> Result<(Fraction, Fraction, Fraction), FractionError> {
+    let mut last = None;
+    for step in Bisection::new(real_number)?.take(n.saturating_add(1)) {
+        last = Some(step?);
+    }
+    let step = last.expect("bisection yields at least one step");
+    Ok((step.left, step.mediant, step.right))
+}
+
+/// One bisection of the [`farey`] search: the bounds and their mediant.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Step<T = Fraction> {
+    pub(crate) left: T,
+    pub(crate) mediant: T,
+    pub(crate) right: T,
+    /// The mediant is within epsilon of the target; this is the last step.
+    pub(crate) converged: bool,
+}
+
+/// Fraction types the [`farey`] search can run over.
+pub(crate) trait SearchBound: Mediant + Copy {
+    fn from_integer(n: u64) -> Result<Self, FractionError>;
+    fn value(&self) -> f64;
+    fn cmp_with_real(&self, real: f64) -> Ordering;
+}
+
+impl SearchBound for Fraction {
+    fn from_integer(n: u64) -> Result<Self, FractionError> {
+        Fraction::new(n, 1)
+    }
+
+    fn value(&self) -> f64 {
+        Fraction::value(self)
+    }
+
+    fn cmp_with_real(&self, real: f64) -> Ordering {
+        Fraction::cmp_with_real(self, real)
+    }
+}
+
+/// The [`farey`] search as an iterator of steps, for callers that need to stop
+/// early or inspect the bounds.
+///
+/// Ends after the converged step, or after yielding the first error.
+#[derive(Debug, Clone)]
+pub(crate) struct Bisection<T = Fraction> {
+    target: f64,
+    left: T,
+    right: T,
+    finished: bool,
+}
+
+impl Bisection {
+    pub(crate) fn new(real_number: f64) -> Result<Self, FractionError> {
+        Self::with_bounds(real_number)
+    }
+}
+
+impl<T: SearchBound> Bisection<T> {
+    /// Starts the search with bounds of type `T`.
+    pub(crate) fn with_bounds(real_number: f64) -> Result<Self, FractionError> {
+        let target = validate_input(real_number)?;
+        // Initialize bounds: the target lies between floor(x) and ceil(x)
+        Ok(Self {
+            target,
+            left: T::from_integer(target.floor() as u64)?,
+            right: T::from_integer(target.ceil() as u64)?,
+            finished: false,
+        })
+    }
+}
+
+impl<T: SearchBound> Iterator for Bisection<T> {
+    type Item = Result<Step<T>, FractionError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        // The mediant always lies strictly between left and right (when they differ)
+        let mediant = match self.left.mediant(&self.right) {
+            Ok(mediant) => mediant,
+            Err(e) => {
+                self.finished = true;
+                return Some(Err(e));
+            }
+        };
+        let mediant_value = mediant.value();
+        let step = Step {
+            left: self.left,
+            mediant,
+            right: self.right,
+            // Convergence: mediant is close enough to target
+            converged: (self.target - mediant_value).abs() < f64::EPSILON,
+        };
+
+        // Binary search: narrow the bounds based on which side the target falls
+        if step.converged {
+            self.finished = true;
+        } else if mediant.cmp_with_real(self.target) == Ordering::Greater {
+            self.right = mediant;
+        } else {
+            self.left = mediant;
+        }
+        Some(Ok(step))
+    }
+}
+
+/// Approximates a real number using its continued fraction convergents.
+///
+/// Where [`farey`] visits every node on the Stern-Brocot path, this jumps
+/// straight from one convergent p_k/q_k to the next using the recurrence
+///
+///   p_k = a_k * p_{k-1} + p_{k-2},   q_k = a_k * q_{k-1} + q_{k-2}
+///
+/// where a_k are the partial quotients of x. A run of a_k identical turns in
+/// the tree collapses into a single step, so large partial quotients (as in
+/// x = 1/1000.5) cost one iteration instead of a thousand. Each a_k is found
+/// by comparing the semiconvergents (p_{k-2} + j p_{k-1})/(q_{k-2} + j q_{k-1})
+/// with x exactly, doubling j and then bisecting, so the partial quotients
+/// are those of the exact value of the `f64` rather than of a floating-point
+/// expansion.
+///
+/// Returns the fraction `farey` returns, the first node on the path within
+/// epsilon of the target, except that integers come back in lowest terms
+/// (3/1 where `farey` gives 6/2). That node is usually a convergent, but a
+/// semiconvergent partway through a run can come within epsilon first, in
+/// which case the run is bisected for it. If the next node would overflow
+/// `u64`, where `farey` fails, returns the last convergent reached instead.
+pub fn farey_convergents(real_number: f64) -> Result<Fraction, FractionError> {
+    farey_convergents_with_steps(real_number, |_| {})
+}
+
+/// Runs [`farey_convergents`], calling `on_step` with each convergent reached,
+/// starting from a0/1.
+///
+/// When the result is a semiconvergent, the last call is with the convergent
+/// that ends its run.
+pub fn farey_convergents_with_steps<F>(real_number: f64, mut on_step: F) -> Result<Fraction, FractionError>
+where
+    F: FnMut(&Fraction),
+{
+    let target = validate_input(real_number)?;
+    let is_close = |frac: &Fraction| (target - frac.value()).abs() < f64::EPSILON;
+
+    let floor = Fraction::new(target.floor() as u64, 1)?;
+    on_step(&floor);
+    if is_close(&floor) {
+        return Ok(floor);
+    }
+    let ceil = Fraction::new(floor.numerator() + 1, 1)?;
+
+    // Each run replaces the bound on one side with moving + j * fixed for
+    // j = 1, 2, ..., until the next of those lands on the other side
+    let first = floor.mediant(&ceil)?;
+    let (mut moving, mut fixed) = match first.cmp_with_real(target) {
+        Ordering::Greater => (ceil, floor),
+        // x > a0 + 1/2, so a1 = 1 and (a0 + 1)/1 is the next convergent
+        Ordering::Less => {
+            on_step(&ceil);
+            (floor, ceil)
+        }
+        Ordering::Equal => {
+            on_step(&first);
+            return Ok(first);
+        }
+    };
+
+    loop {
+        // The nodes of a run lie on the moving bound's side, the last possibly
+        // on x itself
+        let across = match moving.cmp_with_real(target) {
+            Ordering::Greater => Ordering::Less,
+            _ => Ordering::Greater,
+        };
+        let node = |j: u64| semiconvergent(moving, fixed, j);
+        let on_side = |j: u64| node(j).is_some_and(|n| n.cmp_with_real(target) != across);
+        if !on_side(1) {
+            // The first node of the run overflows
+            return Ok(fixed);
+        }
+
+        // Double j past the end of the run, then bisect for its last node
+        let (mut lo, mut hi) = (1u64, 2u64);
+        while hi < u64::MAX && on_side(hi) {
+            lo = hi;
+            hi = hi.saturating_mul(2);
+        }
+        while hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            if on_side(mid) {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        let last = node(lo).expect("nodes on the run's side fit in u64");
+        // The run ends at a convergent only if the node after it crosses over
+        let ended = lo.checked_add(1).and_then(node).is_some_and(|n| n.cmp_with_real(target) == across);
+
+        if ended {
+            on_step(&last);
+        }
+        if is_close(&last) {
+            // Errors shrink along the run, so bisect for the first close node
+            let (mut far, mut near) = (0u64, lo);
+            while near - far > 1 {
+                let mid = far + (near - far) / 2;
+                if node(mid).is_some_and(|n| is_close(&n)) {
+                    near = mid;
+                } else {
+                    far = mid;
+                }
+            }
+            return Ok(node(near).expect("nodes before the last fit in u64"));
+        }
+        if !ended {
+            return Ok(fixed);
+        }
+        (moving, fixed) = (fixed, last);
+    }
+}
+
+/// Returns (a + jc)/(b + jd) for `moving` = a/b and `fixed` = c/d, or `None`
+/// if it does not fit in `u64`.
+fn semiconvergent(moving: Fraction, fixed: Fraction, j: u64) -> Option<Fraction> {
+    let numerator = fixed.numerator().checked_mul(j)?.checked_add(moving.numerator())?;
+    let denominator = fixed.denominator().checked_mul(j)?.checked_add(moving.denominator())?;
+    Fraction::new(numerator, denominator).ok()
+}
+
+/// Returns the closest fraction to `real` with denominator at most
+/// `max_denominator`.
+///
+/// Walks the convergents of `real` until one exceeds the bound. The answer is
+/// then either the last convergent p_k/q_k that fit, or the largest
+/// semiconvergent (p_{k-1} + j p_k)/(q_{k-1} + j q_k) that still fits, which
+/// is the endpoint of the Stern-Brocot interval the search would have been
+/// narrowed to; whichever is closer wins, the smaller denominator on a tie.
+///
+/// Returns `InvalidInput` for a bound of zero.
+pub fn farey_bounded(real_number: f64, max_denominator: u64) -> Result<Fraction, FractionError> {
+    if max_denominator == 0 {
+        return Err(FractionError::InvalidInput);
+    }
+    let real_number = validate_input(real_number)?;
+    let error = |f: &Fraction| (real_number - f.value()).abs();
+
+    let cf = ContinuedFraction::from_f64(real_number)?;
+    // p_{k-2}/q_{k-2} as a raw pair, since it starts at 1/0
+    let mut before = (1u64, 0u64);
+    let mut last: Option<Fraction> = None;
+    for convergent in cf.convergents() {
+        if let Some(last) = last.filter(|_| convergent.denominator() > max_denominator) {
+            let j = (max_denominator - before.1) / last.denominator();
+            let semiconvergent = Fraction::new(
+                before.0 + j * last.numerator(),
+                before.1 + j * last.denominator(),
+            )?;
+            return Ok(if error(&semiconvergent) < error(&last) { semiconvergent } else { last });
+        }
+        if let Some(last) = last {
+            before = (last.numerator(), last.denominator());
+        }
+        last = Some(convergent);
+    }
+    last.ok_or(FractionError::InvalidInput)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The closest p/q with q <= max_denominator by trying every denominator.
+    fn best_by_search(x: f64, max_denominator: u64) -> Fraction {
+        (1..=max_denominator)
+            .map(|q| Fraction::new((q as f64 * x).round() as u64, q).unwrap())
+            .fold(None, |best: Option<Fraction>, candidate| match best {
+                Some(best) if (x - best.value()).abs() <= (x - candidate.value()).abs() => Some(best),
+                _ => Some(candidate),
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn bounded_matches_exhaustive_search() {
+        let targets = [std::f64::consts::PI, std::f64::consts::E, 0.827, 1.0 / 3.0, 0.001, 2.5, 0.618_034];
+        for x in targets {
+            for max_denominator in [1, 2, 3, 7, 10, 57, 100, 113, 1000] {
+                let bounded = farey_bounded(x, max_denominator).unwrap();
+                let best = best_by_search(x, max_denominator);
+                assert!(bounded.denominator() <= max_denominator);
+                // Compare errors, since a tie (2/1 and 3/1 for 2.5) may go either way
+                let error = |f: Fraction| (x - f.value()).abs();
+                assert_eq!(error(bounded), error(best), "{x} up to {max_denominator}");
+            }
+        }
+    }
+
+    #[test]
+    fn bounded_examples() {
+        let pi = std::f64::consts::PI;
+        assert_eq!(farey_bounded(pi, 7).map(Fraction::into_parts), Ok((22, 7)));
+        assert_eq!(farey_bounded(pi, 112).map(Fraction::into_parts), Ok((333, 106)));
+        assert_eq!(farey_bounded(pi, 113).map(Fraction::into_parts), Ok((355, 113)));
+        assert_eq!(farey_bounded(pi, 0), Err(FractionError::InvalidInput));
+        assert_eq!(farey_bounded(f64::NAN, 10), Err(FractionError::InvalidInput));
+    }
+
+    #[test]
+    fn step_n_replays_the_bisection() {
+        for x in [std::f64::consts::PI, 0.827, 17.0 / 23.0, 2.0] {
+            let mut steps = Vec::new();
+            let result = farey_with_steps(x, |left, mediant, right| steps.push((*left, *mediant, *right))).unwrap();
+            for (n, step) in steps.iter().enumerate() {
+                assert_eq!(farey_step_n(x, n).unwrap(), *step, "{x} at step {n}");
+            }
+            // Past convergence the last step repeats
+            let last = farey_step_n(x, steps.len() + 10).unwrap();
+            assert_eq!(last, *steps.last().unwrap());
+            assert_eq!(last.1, result);
+        }
+        let start = farey_step_n(2.5, 0).unwrap();
+        assert_eq!(start, (Fraction::new(2, 1).unwrap(), Fraction::new(5, 2).unwrap(), Fraction::new(3, 1).unwrap()));
+        assert_eq!(farey_step_n(-1.0, 0), Err(FractionError::InvalidInput));
+    }
+
+    #[test]
+    fn convergents_stop_where_the_bisection_does() {
+        let e = std::f64::consts::E;
+        // A semiconvergent, reached before the convergent 325368125/119696244
+        assert_eq!(farey_convergents(e), farey(e));
+        assert_eq!(farey_convergents(e), Ok(Fraction::new(268876667, 98914198).unwrap()));
+        for x in [std::f64::consts::PI, 0.827, 1.0 / 1000.5, 17.0 / 23.0, 0.5, 2.5] {
+            assert_eq!(farey_convergents(x), farey(x), "{x}");
+        }
+        // The bisection would take ~2^52 steps to get here, one run of n/(n+1)
+        let below_one = farey_convergents(1.0 - f64::EPSILON / 2.0).unwrap();
+        assert_eq!(below_one.numerator() + 1, below_one.denominator());
+        assert!((1.0 - f64::EPSILON / 2.0 - below_one.value()).abs() < f64::EPSILON);
+        assert_eq!(farey_convergents(3.0), Ok(Fraction::new(3, 1).unwrap()));
+        assert_eq!(farey_convergents(-1.0), Err(FractionError::InvalidInput));
+    }
+
+    #[test]
+    fn convergents_of_pi() {
+        let mut convergents = Vec::new();
+        let push = |c: &Fraction| convergents.push((c.numerator(), c.denominator()));
+        farey_convergents_with_steps(std::f64::consts::PI, push).unwrap();
+        assert_eq!(convergents[..5], [(3, 1), (22, 7), (333, 106), (355, 113), (103993, 33102)]);
+        // Each step is the recurrence with the partial quotients 3; 7, 15, 1, 292, ...
+        let terms = ContinuedFraction::from_f64(std::f64::consts::PI).unwrap();
+        assert_eq!(terms.terms()[..5], [3, 7, 15, 1, 292]);
+        assert_eq!(terms.convergents().count(), convergents.len());
+    }
+}
@@ -0,0 +1,784 @@
+//! The Farey sequence F_n: reduced fractions in [0, 1] with denominator <= n.
+//!
+//! Consecutive terms a/b < c/d of F_n satisfy bc - ad = 1 and b + d > n; the
+//! second condition says their mediant has not yet appeared.
+
+use std::cell::OnceCell;
+use std::collections::HashMap;
+
+use crate::number_theory::{gcd, mod_inverse};
+use crate::{Fraction, FractionError, ReducedFraction};
+
+/// Iterator over F_n in increasing order, from 0/1 to 1/1.
+///
+/// Each term follows from the previous two: after a/b, c/d comes
+/// (kc - a)/(kd - b) with k = floor((n + b) / d), so each step is O(1). The
+/// recurrence is symmetric, so the same step run from the right end walks the
+/// sequence backwards for [`DoubleEndedIterator`].
+///
+/// The length is computed the first time it is asked for (by `len`,
+/// `size_hint` or `collect`) and then cached. It takes O(n / 1024) time with a
+/// fixed-size sieve rather than the O(n) memory of [`farey_sequence_len`], and
+/// nothing at all past n = 2^33, where |F_n| no longer fits in `usize`: there
+/// `size_hint` is `(usize::MAX, None)` and `len` panics.
+#[derive(Debug, Clone)]
+pub struct FareySequence {
+    n: u64,
+    /// The term `next` will yield.
+    front: (u64, u64),
+    /// The term after `front`.
+    after_front: (u64, u64),
+    /// The term `next_back` will yield.
+    back: (u64, u64),
+    /// The term before `back`.
+    before_back: (u64, u64),
+    /// The two ends have met, or n is 0.
+    finished: bool,
+    /// Terms yielded from either end.
+    yielded: u64,
+    /// |F_n|, or `None` if it does not fit in `usize`.
+    len: OnceCell<Option<usize>>,
+}
+
+impl FareySequence {
+    /// Starts F_n. F_0 has no terms.
+    pub fn new(n: u64) -> Self {
+        Self {
+            n,
+            front: (0, 1),
+            after_front: (1, n),
+            back: (1, 1),
+            before_back: (n.saturating_sub(1), n),
+            finished: n == 0,
+            yielded: 0,
+            len: OnceCell::new(),
+        }
+    }
+
+    /// Returns the terms of F_n that first appear in F_depth: the reduced
+    /// fractions with denominator exactly `depth`, in increasing order.
+    ///
+    /// There are φ(depth) of them, except at depth 1 where F_1 starts with
+    /// both 0/1 and 1/1. Empty if `depth` is 0 or greater than n.
+    pub fn fractions_at_depth(n: u64, depth: u64) -> impl Iterator<Item = Fraction> {
+        // gcd(c, 0) is c, so depth 0 only lets c = 1 through; rule it out here
+        let numerators = (depth > 0 && depth <= n).then_some(0..=depth);
+        numerators
+            .into_iter()
+            .flatten()
+            .filter(move |&c| gcd(c, depth) == 1)
+            .filter_map(move |c| Fraction::new(c, depth).ok())
+    }
+
+    /// The number of terms still to come, or `None` if it does not fit in `usize`.
+    fn remaining(&self) -> Option<usize> {
+        if self.finished {
+            return Some(0);
+        }
+        let len = *self.len.get_or_init(|| {
+            // |F_n| is about 3n²/π², past usize::MAX from here on
+            (self.n <= 1 << 33).then(|| usize::try_from(1 + totient_sum(self.n)).ok()).flatten()
+        });
+        // Everything yielded was counted in the length, so this cannot underflow
+        len.map(|len| len - self.yielded as usize)
+    }
+}
+
+impl Iterator for FareySequence {
+    type Item = Fraction;
+
+    fn next(&mut self) -> Option<Fraction> {
+        if self.finished {
+            return None;
+        }
+        let term = self.front;
+        // The ends meet on the last remaining term
+        if term == self.back {
+            self.finished = true;
+        } else {
+            self.front = self.after_front;
+            // Nothing follows 1/1, so only look past terms below it
+            if self.front != (1, 1) {
+                self.after_front = beyond(term, self.front, self.n);
+            }
+        }
+        self.yielded += 1;
+        Fraction::new(term.0, term.1).ok()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining() {
+            Some(len) => (len, Some(len)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+impl DoubleEndedIterator for FareySequence {
+    fn next_back(&mut self) -> Option<Fraction> {
+        if self.finished {
+            return None;
+        }
+        let term = self.back;
+        // The ends meet on the last remaining term
+        if term == self.front {
+            self.finished = true;
+        } else {
+            self.back = self.before_back;
+            // Nothing precedes 0/1, so only look past terms above it
+            if self.back != (0, 1) {
+                self.before_back = beyond(term, self.back, self.n);
+            }
+        }
+        self.yielded += 1;
+        Fraction::new(term.0, term.1).ok()
+    }
+}
+
+impl ExactSizeIterator for FareySequence {
+    /// Panics if the remaining length does not fit in `usize`.
+    fn len(&self) -> usize {
+        self.remaining().expect("F_n has more than usize::MAX terms")
+    }
+}
+
+/// Returns the term of F_n beyond `near` on the far side from `far`, where
+/// `far` and `near` are consecutive in F_n (in either order) and `near` is not
+/// an end of the sequence.
+fn beyond(far: (u64, u64), near: (u64, u64), n: u64) -> (u64, u64) {
+    let k = (n as u128 + far.1 as u128) / near.1 as u128;
+    // Both components are at most n, so the narrowing casts are lossless
+    let beyond = (k * near.0 as u128 - far.0 as u128, k * near.1 as u128 - far.1 as u128);
+    (beyond.0 as u64, beyond.1 as u64)
+}
+
+/// Returns the term of F_n after `right`, given the term `left` just before
+/// it, or `None` if `right` is 1/1.
+///
+/// One step of the recurrence [`FareySequence`] uses. Returns `None` as well
+/// if the two are not consecutive in F_n.
+pub fn farey_next(left: Fraction, right: Fraction, n: u64) -> Option<Fraction> {
+    if !farey_adjacent(left, right, n) || right.numerator() == right.denominator() {
+        return None;
+    }
+    let (p, q) = beyond(left.into_parts(), right.into_parts(), n);
+    Fraction::new(p, q).ok()
+}
+
+/// Returns the term of F_n before `left`, given the term `right` just after
+/// it, or `None` if `left` is 0/1.
+///
+/// The mirror image of [`farey_next`]. Returns `None` as well if the two are
+/// not consecutive in F_n.
+pub fn farey_prev(left: Fraction, right: Fraction, n: u64) -> Option<Fraction> {
+    if !farey_adjacent(left, right, n) || left.numerator() == 0 {
+        return None;
+    }
+    let (p, q) = beyond(right.into_parts(), left.into_parts(), n);
+    Fraction::new(p, q).ok()
+}
+
+/// Returns `frac` with up to `k` terms of F_n on either side of it, in order.
+///
+/// The window is cut short at 0/1 and 1/1. Finds the immediate neighbors with
+/// [`farey_neighbors`] and walks outwards with [`farey_prev`] and
+/// [`farey_next`], so it costs O(k log n) rather than a pass over F_n.
+///
+/// Returns `InvalidInput` if `frac` is not a term of F_n.
+pub fn farey_window(n: u64, frac: Fraction, k: usize) -> Result<Vec<Fraction>, FractionError> {
+    if !is_in_farey(frac, n) {
+        return Err(FractionError::InvalidInput);
+    }
+    let (p, q) = frac.into_parts();
+    // The ends have only one neighbor each
+    let (left, right) = match (p, q) {
+        (0, _) if n == 1 => (None, Some(Fraction::new(1, 1)?)),
+        (0, _) => (None, Some(Fraction::new(1, n)?)),
+        (1, 1) => (Some(Fraction::new(n - 1, n)?), None),
+        _ => farey_neighbors(frac, n).map(|(l, r)| (Some(l), Some(r)))?,
+    };
+
+    let mut before: Vec<Fraction> = Vec::new();
+    let mut here = frac;
+    let mut next = left;
+    while let Some(term) = next.filter(|_| before.len() < k) {
+        before.push(term);
+        next = farey_prev(term, here, n);
+        here = term;
+    }
+
+    let mut window: Vec<Fraction> = before.into_iter().rev().collect();
+    window.push(frac);
+    let (mut here, mut next, mut after) = (frac, right, 0);
+    while let Some(term) = next.filter(|_| after < k) {
+        window.push(term);
+        after += 1;
+        next = farey_next(here, term, n);
+        here = term;
+    }
+    Ok(window)
+}
+
+/// Collects F_n into a `Vec`, for random access into small sequences.
+///
+/// |F_n| grows like 3n²/π², so this refuses n above 1000 (about 300,000
+/// terms) with `InvalidInput`; iterate [`FareySequence`] for anything larger.
+pub fn farey_sequence_to_vec(n: u64) -> Result<Vec<Fraction>, FractionError> {
+    if n > 1000 {
+        return Err(FractionError::InvalidInput);
+    }
+    let mut terms = Vec::with_capacity(farey_sequence_len(n) as usize);
+    terms.extend(FareySequence::new(n));
+    Ok(terms)
+}
+
+/// Iterates over the terms of F_n that satisfy `predicate`, in order.
+///
+/// Still walks every term with the O(1) recurrence, but nothing is collected,
+/// so e.g. the n unit fractions of F_n come out in O(n²) time and O(1) memory.
+pub fn farey_filtered(n: u64, predicate: impl Fn(Fraction) -> bool) -> impl Iterator<Item = Fraction> {
+    FareySequence::new(n).filter(move |&frac| predicate(frac))
+}
+
+/// Returns |F_n| = 1 + φ(1) + φ(2) + ... + φ(n), or 0 for n = 0.
+///
+/// Computes Euler's totient for every k <= n with a sieve, in O(n log log n)
+/// time and O(n) memory.
+pub fn farey_sequence_len(n: u64) -> u64 {
+    if n == 0 {
+        return 0;
+    }
+    let n = n as usize;
+    let mut totient: Vec<u64> = (0..=n as u64).collect();
+    for p in 2..=n {
+        // Untouched entries are primes
+        if totient[p] == p as u64 {
+            for multiple in (p..=n).step_by(p) {
+                totient[multiple] -= totient[multiple] / p as u64;
+            }
+        }
+    }
+    1 + totient[1..].iter().sum::<u64>()
+}
+
+/// Returns φ(1) + φ(2) + ... + φ(n) without a sieve of size n.
+///
+/// Counting the pairs 1 <= a <= b <= v by their gcd g gives
+/// v(v+1)/2 = Σ_g S(v / g), where S is this sum, so S(v) is v(v+1)/2 minus
+/// S(v / g) for every g >= 2. The quotients
+/// n / g take only O(√n) distinct values; those above the sieve limit L are
+/// n / d for d <= n / L and are memoized by d, in O(n / √L) time overall.
+fn totient_sum(n: u64) -> u128 {
+    const SIEVE_LIMIT: u64 = 1 << 20;
+    let limit = n.min(SIEVE_LIMIT);
+
+    // Running sums of the totient up to the limit
+    let mut small: Vec<u64> = (0..=limit).collect();
+    for p in 2..=limit as usize {
+        if small[p] == p as u64 {
+            for multiple in (p..=limit as usize).step_by(p) {
+                small[multiple] -= small[multiple] / p as u64;
+            }
+        }
+    }
+    for k in 1..small.len() {
+        small[k] += small[k - 1];
+    }
+    if n == limit {
+        return small[n as usize] as u128;
+    }
+
+    // large[d] = S(n / d) for every d with n / d above the limit
+    let count = (n / (limit + 1)) as usize;
+    let mut large = vec![0u128; count + 1];
+    for d in (1..=count).rev() {
+        let v = n / d as u64;
+        let mut sum = v as u128 * (v as u128 + 1) / 2;
+        let mut g = 2;
+        while g <= v {
+            let q = v / g;
+            let last = v / q;
+            // q = n / (d * g), so a large q has d * g <= count
+            let s = if q <= limit { small[q as usize] as u128 } else { large[d * g as usize] };
+            sum -= (last - g + 1) as u128 * s;
+            g = last + 1;
+        }
+        large[d] = sum;
+    }
+    large[1]
+}
+
+/// Counts the terms of F_n that are <= `frac` (so 0/1 has rank 1).
+///
+/// `frac` need not be a term of F_n itself. For each denominator d there are
+/// floor(d * frac) numerators c >= 1 with c/d <= frac; subtracting the counts
+/// already attributed to the proper divisors of d leaves the reduced ones.
+/// Runs in O(n log n) time and O(n) memory.
+pub fn farey_rank(frac: Fraction, n: u64) -> u64 {
+    if n == 0 {
+        return 0;
+    }
+    // Everything in F_n is at most 1/1
+    if frac.numerator() >= frac.denominator() {
+        return farey_sequence_len(n);
+    }
+
+    let (p, q) = (frac.numerator() as u128, frac.denominator() as u128);
+    let n = n as usize;
+    let mut counts: Vec<u64> = (0..=n).map(|d| (d as u128 * p / q) as u64).collect();
+    for d in 1..=n {
+        let reduced = counts[d];
+        for multiple in (2 * d..=n).step_by(d) {
+            counts[multiple] -= reduced;
+        }
+    }
+    1 + counts[1..].iter().sum::<u64>()
+}
+
+/// Returns the k-th term of F_n, counting from 1 (so `farey_kth(1, n)` is 0/1).
+///
+/// Descends the Stern-Brocot tree between 0/1 and 1/1, using [`farey_rank`]
+/// to decide which side of each node the k-th term lies on. A run of turns in
+/// the same direction is found with a binary search rather than one step at a
+/// time, so only O(log n) runs of O(log n) rank queries are needed, for
+/// O(n log^3 n) overall instead of walking k terms.
+///
+/// Returns `InvalidInput` if k is 0 or greater than `farey_sequence_len(n)`.
+pub fn farey_kth(k: u64, n: u64) -> Result<Fraction, FractionError> {
+    let len = farey_sequence_len(n);
+    if k == 0 || k > len {
+        return Err(FractionError::InvalidInput);
+    }
+    if k == 1 {
+        return Fraction::new(0, 1);
+    }
+    if k == len {
+        return Fraction::new(1, 1);
+    }
+
+    let rank = |(p, q): (u64, u64)| farey_rank(Fraction::new(p, q).expect("denominators are positive"), n);
+    // Invariant: rank(lo) < k < rank(hi), lo and hi are Stern-Brocot neighbors
+    let (mut lo, mut hi) = ((0u64, 1u64), (1u64, 1u64));
+    loop {
+        // Every term strictly between lo and hi has a denominator at least
+        // that of their mediant, and the k-th term is one of them
+        let mediant = (lo.0 + hi.0, lo.1 + hi.1);
+        let r = rank(mediant);
+        if r == k {
+            return Fraction::new(mediant.0, mediant.1);
+        }
+
+        // Turning right repeatedly moves lo through lo + j*hi (rank increasing
+        // in j); turning left moves hi through j*lo + hi (rank decreasing)
+        let turning_right = r < k;
+        let (base, step) = if turning_right { (lo, hi) } else { (hi, lo) };
+        let node = |j: u64| (base.0 + j * step.0, base.1 + j * step.1);
+        let on_near_side = |j: u64| {
+            let r = rank(node(j));
+            if turning_right { r <= k } else { r >= k }
+        };
+
+        // Largest j with node(j) in F_n and still on the near side of k
+        let (mut good, mut bad) = (1u64, (n - base.1) / step.1 + 1);
+        while bad - good > 1 {
+            let mid = good + (bad - good) / 2;
+            if on_near_side(mid) {
+                good = mid;
+            } else {
+                bad = mid;
+            }
+        }
+
+        let reached = node(good);
+        if rank(reached) == k {
+            return Fraction::new(reached.0, reached.1);
+        }
+        if turning_right {
+            lo = reached;
+        } else {
+            hi = reached;
+        }
+    }
+}
+
+/// Returns true if `frac` is a term of F_n: reduced, in [0, 1], denominator <= n.
+fn is_in_farey(frac: Fraction, n: u64) -> bool {
+    let (p, q) = (frac.numerator(), frac.denominator());
+    q <= n && p <= q && gcd(p, q) == 1
+}
+
+/// Returns true if `a` immediately precedes `b` in F_n.
+pub fn farey_adjacent(a: Fraction, b: Fraction, n: u64) -> bool {
+    if !is_in_farey(a, n) || !is_in_farey(b, n) {
+        return false;
+    }
+    Fraction::cross_product(a, b) == -1 && a.denominator() as u128 + b.denominator() as u128 > n as u128
+}
+
+/// Returns true if a < b, both are terms of F_n, and no term of F_n lies
+/// strictly between them.
+///
+/// The same test as [`farey_adjacent`], spelled out: the order matters, so
+/// `are_consecutive_farey_neighbors(b, a, n)` is false. Checking it for every
+/// pair [`FareySequence`] yields confirms the recurrence.
+pub fn are_consecutive_farey_neighbors(a: Fraction, b: Fraction, n: u64) -> bool {
+    farey_adjacent(a, b, n)
+}
+
+/// Returns the terms immediately before and after `frac` in F_n.
+///
+/// For p/q the left neighbor a/b solves pb - qa = 1 with b as large as
+/// possible (b <= n), so b is the inverse of p modulo q lifted into
+/// (n - q, n]. The right neighbor then follows from the Farey recurrence: with
+/// k = floor((n + b) / q), it is (kp - a)/(kq - b).
+///
+/// Returns `InvalidInput` if `frac` is not a term of F_n, or is 0/1 or 1/1
+/// (which have only one neighbor in F_n).
+pub fn farey_neighbors(frac: Fraction, n: u64) -> Result<(Fraction, Fraction), FractionError> {
+    let (p, q) = (frac.numerator(), frac.denominator());
+    if !is_in_farey(frac, n) || p == 0 || p == q {
+        return Err(FractionError::InvalidInput);
+    }
+
+    let inverse = mod_inverse(p, q) as u128;
+    let (p, q, n) = (p as u128, q as u128, n as u128);
+    let b = inverse + (n - inverse) / q * q;
+    let a = (p * b - 1) / q;
+
+    let k = (n + b) / q;
+    let (c, d) = (k * p - a, k * q - b);
+
+    // Every component is at most n, so the narrowing casts are lossless
+    Ok((
+        Fraction::new(a as u64, b as u64)?,
+        Fraction::new(c as u64, d as u64)?,
+    ))
+}
+
+/// Returns the largest term of F_n that is at most `frac`.
+///
+/// This is `frac` itself (reduced) if it is a term of F_n, its left neighbor
+/// in F_n if it falls between two terms, and 1/1 for anything above 1.
+/// Returns `InvalidInput` for n = 0, where F_n is empty.
+pub fn floor_to_farey(frac: Fraction, n: u64) -> Result<Fraction, FractionError> {
+    if n == 0 {
+        return Err(FractionError::InvalidInput);
+    }
+    if frac.numerator() >= frac.denominator() {
+        return Fraction::new(1, 1);
+    }
+    Ok(farey_bracket(frac.reduce(), n)?.0)
+}
+
+/// Returns the smallest term of F_n that is at least `frac`.
+///
+/// The mirror image of [`floor_to_farey`]. Returns `InvalidInput` for n = 0
+/// and for `frac` above 1, which no term of F_n reaches.
+pub fn ceil_to_farey(frac: Fraction, n: u64) -> Result<Fraction, FractionError> {
+    if n == 0 || frac.numerator() > frac.denominator() {
+        return Err(FractionError::InvalidInput);
+    }
+    Ok(farey_bracket(frac.reduce(), n)?.1)
+}
+
+/// Returns the terms of F_n either side of the reduced `frac` in [0, 1], or
+/// `frac` twice if it is a term.
+///
+/// Descends the Stern-Brocot tree from the bounds 0/1 and 1/1 towards `frac`,
+/// stopping at the first mediant whose denominator exceeds n; the bounds at
+/// that point are consecutive in F_n. Each run of turns the same way is taken
+/// in one step, so this costs one step per partial quotient of `frac`.
+fn farey_bracket(frac: Fraction, n: u64) -> Result<(Fraction, Fraction), FractionError> {
+    if frac.denominator() <= n {
+        return Ok((frac, frac));
+    }
+    let (p, q) = (frac.numerator() as u128, frac.denominator() as u128);
+    let n = n as u128;
+    let (mut lo, mut hi) = ((0u128, 1u128), (1u128, 1u128));
+    // frac has a denominator above n, so it never equals a bound or mediant
+    // and both differences below stay positive
+    while lo.1 + hi.1 <= n {
+        let below_hi = hi.0 * q - p * hi.1;
+        let above_lo = p * lo.1 - lo.0 * q;
+        if (lo.0 + hi.0) * q < p * (lo.1 + hi.1) {
+            // The largest k with lo + k*hi still below frac and within F_n
+            let k = ((above_lo - 1) / below_hi).min((n - lo.1) / hi.1);
+            lo = (lo.0 + k * hi.0, lo.1 + k * hi.1);
+        } else {
+            let k = ((below_hi - 1) / above_lo).min((n - hi.1) / lo.1);
+            hi = (hi.0 + k * lo.0, hi.1 + k * lo.1);
+        }
+    }
+    // Every component is at most n, so the narrowing casts are lossless
+    Ok((
+        Fraction::new(lo.0 as u64, lo.1 as u64)?,
+        Fraction::new(hi.0 as u64, hi.1 as u64)?,
+    ))
+}
+
+/// Returns the Farey graph on F_n: each term mapped to the terms it is
+/// adjacent to in some F_k with k <= n, in increasing order.
+///
+/// Two reduced fractions a/b and c/d are Farey neighbors iff |bc - ad| = 1.
+/// Each term p/q with q >= 2 first appears in F_q between its two neighbors
+/// there, its Stern-Brocot parents, so the edges are those pairs plus 0/1 -
+/// 1/1, for 2|F_n| - 3 edges in all.
+pub fn farey_adjacency_list(n: u64) -> HashMap<Fraction, Vec<Fraction>> {
+    let mut adjacency: HashMap<Fraction, Vec<Fraction>> = HashMap::new();
+    let mut connect = |a: Fraction, b: Fraction| {
+        adjacency.entry(a).or_default().push(b);
+        adjacency.entry(b).or_default().push(a);
+    };
+    for frac in FareySequence::new(n) {
+        if frac.denominator() == 1 {
+            if frac.numerator() == 1 {
+                connect(Fraction::new(0, 1).expect("denominator is positive"), frac);
+            }
+            continue;
+        }
+        let (left, right) = farey_neighbors(frac, frac.denominator())
+            .expect("terms other than 0/1 and 1/1 have two neighbors in F_q");
+        connect(left, frac);
+        connect(frac, right);
+    }
+    for neighbors in adjacency.values_mut() {
+        neighbors.sort_by_key(|&f| ReducedFraction::from(f));
+    }
+    adjacency
+}
+
+/// Returns the number of Farey neighbors of `frac` in F_n, or 0 if `frac` is
+/// not a term of F_n.
+///
+/// Besides the two neighbors a/b, c/d it has in F_q, p/q is adjacent to the
+/// terms that later split those gaps: the ones with denominators b + jq and
+/// d + jq up to n. 0/1 and 1/1 are adjacent to each other and to every 1/s
+/// and (s-1)/s respectively, so both have degree n.
+pub fn farey_graph_degree(frac: Fraction, n: u64) -> u64 {
+    if !is_in_farey(frac, n) {
+        return 0;
+    }
+    let q = frac.denominator();
+    if q == 1 {
+        return n;
+    }
+    let (left, right) =
+        farey_neighbors(frac, q).expect("terms other than 0/1 and 1/1 have two neighbors in F_q");
+    2 + (n - left.denominator()) / q + (n - right.denominator()) / q
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frac(numerator: u64, denominator: u64) -> Fraction {
+        Fraction::new(numerator, denominator).unwrap()
+    }
+
+    #[test]
+    fn neighbors_satisfy_the_determinant_identity() {
+        for n in 2..=25 {
+            for term in FareySequence::new(n).filter(|t| t.numerator() > 0 && t.numerator() < t.denominator()) {
+                let (left, right) = farey_neighbors(term, n).unwrap();
+                for (a, c) in [(left, term), (term, right)] {
+                    let bc = a.denominator() as i128 * c.numerator() as i128;
+                    let ad = a.numerator() as i128 * c.denominator() as i128;
+                    assert_eq!(bc - ad, 1, "{a:?} {c:?} in F_{n}");
+                    assert!(farey_adjacent(a, c, n));
+                }
+                assert_eq!(term.neighbors_in_farey(n), Ok((left, right)));
+            }
+        }
+    }
+
+    #[test]
+    fn neighbors_examples_and_errors() {
+        assert_eq!(farey_neighbors(frac(1, 2), 5), Ok((frac(2, 5), frac(3, 5))));
+        assert_eq!(farey_neighbors(frac(1, 3), 3), Ok((frac(0, 1), frac(1, 2))));
+        assert_eq!(farey_neighbors(frac(0, 1), 5), Err(FractionError::InvalidInput));
+        assert_eq!(farey_neighbors(frac(1, 1), 5), Err(FractionError::InvalidInput));
+        assert_eq!(farey_neighbors(frac(2, 4), 5), Err(FractionError::InvalidInput));
+        assert_eq!(farey_neighbors(frac(1, 7), 5), Err(FractionError::InvalidInput));
+        let (left, right) = farey_neighbors(frac(1, 2), u64::MAX).unwrap();
+        assert!(farey_adjacent(left, frac(1, 2), u64::MAX) && farey_adjacent(frac(1, 2), right, u64::MAX));
+    }
+
+    #[test]
+    fn kth_and_rank_match_the_enumerated_sequence() {
+        for n in 1..=40 {
+            let terms: Vec<Fraction> = FareySequence::new(n).collect();
+            assert_eq!(terms.len() as u64, farey_sequence_len(n));
+            for (i, &term) in terms.iter().enumerate() {
+                let k = i as u64 + 1;
+                assert_eq!(farey_kth(k, n), Ok(term), "k = {k} in F_{n}");
+                assert_eq!(farey_rank(term, n), k, "{term:?} in F_{n}");
+            }
+            assert_eq!(farey_kth(0, n), Err(FractionError::InvalidInput));
+            assert_eq!(farey_kth(terms.len() as u64 + 1, n), Err(FractionError::InvalidInput));
+        }
+    }
+
+    #[test]
+    fn rank_between_terms() {
+        let terms: Vec<Fraction> = FareySequence::new(7).collect();
+        for (p, q) in [(1, 100), (3, 10), (99, 100), (5, 4)] {
+            let x = frac(p, q);
+            let expected = terms.iter().filter(|t| Fraction::cross_product(**t, x) <= 0).count() as u64;
+            assert_eq!(farey_rank(x, 7), expected, "{x:?}");
+        }
+        assert_eq!(farey_rank(frac(1, 2), 0), 0);
+    }
+
+    #[test]
+    fn reverse_traversal_matches_forward() {
+        for n in 0..=30 {
+            let forward: Vec<Fraction> = FareySequence::new(n).collect();
+            let mut backward: Vec<Fraction> = FareySequence::new(n).rev().collect();
+            backward.reverse();
+            assert_eq!(backward, forward, "F_{n}");
+        }
+    }
+
+    #[test]
+    fn ends_meet_in_the_middle() {
+        let forward: Vec<Fraction> = FareySequence::new(9).collect();
+        let mut sequence = FareySequence::new(9);
+        let (mut front, mut back) = (Vec::new(), Vec::new());
+        while let Some(term) = sequence.next() {
+            front.push(term);
+            back.extend(sequence.next_back());
+        }
+        back.reverse();
+        front.append(&mut back);
+        assert_eq!(front, forward);
+    }
+
+    #[test]
+    fn huge_orders_are_lazy() {
+        let start: Vec<Fraction> = FareySequence::new(1 << 36).take(3).collect();
+        assert_eq!(start, [frac(0, 1), frac(1, 1 << 36), frac(1, (1 << 36) - 1)]);
+        assert_eq!(FareySequence::new(1 << 36).size_hint(), (usize::MAX, None));
+        assert_eq!(FareySequence::new(0).size_hint(), (0, Some(0)));
+        // |F_n| for n = 10^9, from the 304 quadrillion pairs counted by OEIS A064018
+        assert_eq!(FareySequence::new(1_000_000_000).len(), 303_963_551_173_008_415);
+    }
+
+    #[test]
+    fn exact_size_counts_down_from_both_ends() {
+        for n in 0..=30 {
+            let mut sequence = FareySequence::new(n);
+            let mut len = farey_sequence_len(n) as usize;
+            assert_eq!(sequence.len(), len, "F_{n}");
+            while sequence.next().is_some() {
+                len -= 1;
+                assert_eq!(sequence.size_hint(), (len, Some(len)), "F_{n}");
+                if sequence.next_back().is_some() {
+                    len -= 1;
+                }
+                assert_eq!(sequence.len(), len, "F_{n}");
+            }
+            assert_eq!(len, 0);
+        }
+    }
+
+    #[test]
+    fn totient_sum_matches_the_sieve() {
+        for n in (1..2000).chain([1 << 20, (1 << 20) + 1, 3_000_000, 3_141_592]) {
+            assert_eq!(1 + totient_sum(n), farey_sequence_len(n) as u128, "{n}");
+        }
+    }
+
+    #[test]
+    fn fractions_at_depth_have_that_denominator() {
+        for depth in 2..=30 {
+            let phi = (1..=depth).filter(|&c| gcd(c, depth) == 1).count();
+            let terms: Vec<_> = FareySequence::fractions_at_depth(30, depth).collect();
+            assert_eq!(terms.len(), phi, "depth {depth}");
+            assert!(terms.iter().all(|f| f.denominator() == depth));
+            assert!(terms.windows(2).all(|pair| Fraction::cross_product(pair[0], pair[1]) < 0));
+        }
+        assert_eq!(FareySequence::fractions_at_depth(5, 1).collect::<Vec<_>>(), [frac(0, 1), frac(1, 1)]);
+        assert_eq!(FareySequence::fractions_at_depth(5, 6).count(), 0);
+        assert_eq!(FareySequence::fractions_at_depth(5, 0).count(), 0);
+    }
+
+    #[test]
+    fn depths_partition_the_sequence() {
+        let mut terms: Vec<_> = (1..=12).flat_map(|depth| FareySequence::fractions_at_depth(12, depth)).collect();
+        terms.sort_by_key(|&f| ReducedFraction::from(f));
+        assert_eq!(terms, FareySequence::new(12).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn adjacency_list_is_the_farey_graph() {
+        for n in 2..=12 {
+            let terms: Vec<_> = FareySequence::new(n).collect();
+            let adjacency = farey_adjacency_list(n);
+            assert_eq!(adjacency.len(), terms.len());
+            let edges: usize = adjacency.values().map(Vec::len).sum::<usize>() / 2;
+            assert_eq!(edges, 2 * terms.len() - 3, "F_{n}");
+            for pair in terms.windows(2) {
+                assert!(adjacency[&pair[0]].contains(&pair[1]));
+            }
+            for (frac, neighbors) in &adjacency {
+                assert!(neighbors.len() >= 2, "{frac:?} in F_{n}");
+                assert!(neighbors.iter().all(|&other| Fraction::cross_product(*frac, other).abs() == 1));
+                assert_eq!(farey_graph_degree(*frac, n), neighbors.len() as u64, "{frac:?} in F_{n}");
+            }
+        }
+    }
+
+    #[test]
+    fn graph_degree_examples() {
+        // 1/2 meets 0/1 and 1/1 in F_2, then 1/3, 2/3, 2/5, 3/5, ... as they appear
+        assert_eq!(farey_graph_degree(frac(1, 2), 2), 2);
+        assert_eq!(farey_graph_degree(frac(1, 2), 5), 6);
+        assert_eq!(farey_graph_degree(frac(0, 1), 7), 7);
+        assert_eq!(farey_graph_degree(frac(1, 7), 5), 0);
+        assert_eq!(farey_graph_degree(frac(2, 4), 5), 0);
+    }
+
+    #[test]
+    fn to_vec_collects_the_sequence() {
+        assert_eq!(farey_sequence_to_vec(0), Ok(Vec::new()));
+        assert_eq!(farey_sequence_to_vec(1), Ok(vec![frac(0, 1), frac(1, 1)]));
+        assert_eq!(
+            farey_sequence_to_vec(5).unwrap(),
+            [
+                frac(0, 1), frac(1, 5), frac(1, 4), frac(1, 3), frac(2, 5), frac(1, 2),
+                frac(3, 5), frac(2, 3), frac(3, 4), frac(4, 5), frac(1, 1),
+            ]
+        );
+        let terms = farey_sequence_to_vec(1000).unwrap();
+        assert_eq!(terms.len() as u64, farey_sequence_len(1000));
+        assert_eq!(terms.capacity(), terms.len());
+        assert_eq!(farey_sequence_to_vec(1001), Err(FractionError::InvalidInput));
+    }
+
+    #[test]
+    fn filtered_unit_fractions() {
+        assert_eq!(farey_filtered(10, |f| f.is_unit()).count(), 10);
+        let units: Vec<_> = farey_filtered(4, |f| f.is_unit()).collect();
+        assert_eq!(units, [frac(1, 4), frac(1, 3), frac(1, 2), frac(1, 1)]);
+        assert_eq!(farey_filtered(10, |_| true).count() as u64, farey_sequence_len(10));
+        assert_eq!(farey_filtered(0, |_| true).count(), 0);
+        assert!(!frac(0, 1).is_unit() && !frac(2, 3).is_unit() && frac(2, 6).is_unit());
+    }
+
+    #[test]
+    fn consecutive_neighbors_follow_the_sequence() {
+        for n in 1..=15 {
+            let terms: Vec<_> = FareySequence::new(n).collect();
+            for (i, &a) in terms.iter().enumerate() {
+                for (j, &b) in terms.iter().enumerate() {
+                    assert_eq!(are_consecutive_farey_neighbors(a, b, n), j == i + 1, "{a:?} {b:?} F_{n}");
+                }
+            }
+        }
+        // 1/3 and 1/2 stay neighbors until 2/5 enters at F_5, and order matters
+        assert!(are_consecutive_farey_neighbors(frac(1, 3), frac(1, 2), 4));
+        assert!(!are_consecutive_farey_neighbors(frac(1, 3), frac(1, 2), 5));
+        assert!(!are_consecutive_farey_neighbors(frac(1, 2), frac(1, 3), 4));
+        assert!(!are_consecutive_farey_neighbors(frac(2, 6), frac(1, 2), 4));
+    }
+}
@@ -0,0 +1,64 @@
+//! C bindings, enabled with the `c-ffi` feature.
+//!
+//! `build.rs` runs cbindgen over this module and writes `mediant.h` to the
+//! build script's `OUT_DIR`. `tests/ffi_test.sh` builds the library as a
+//! cdylib and runs the C smoke test in `tests/ffi_test.c` against it.
+//! Functions that return `i32` use 0 for success and 1 for error; on error the
+//! output pointers are left untouched.
+
+use crate::{farey, Fraction};
+
+/// Approximates `x`, writing the numerator and denominator to the out pointers.
+///
+/// # Safety
+///
+/// `num_out` and `den_out` must each be null or valid for writing a `u64`.
+#[no_mangle]
+pub unsafe extern "C" fn farey_approx(x: f64, num_out: *mut u64, den_out: *mut u64) -> i32 {
+    if num_out.is_null() || den_out.is_null() {
+        return 1;
+    }
+    match farey(x) {
+        Ok(approx) => {
+            *num_out = approx.numerator();
+            *den_out = approx.denominator();
+            0
+        }
+        Err(_) => 1,
+    }
+}
+
+/// Computes the mediant of `a_n/a_d` and `b_n/b_d`.
+///
+/// # Safety
+///
+/// `out_n` and `out_d` must each be null or valid for writing a `u64`.
+#[no_mangle]
+pub unsafe extern "C" fn fraction_mediant(
+    a_n: u64,
+    a_d: u64,
+    b_n: u64,
+    b_d: u64,
+    out_n: *mut u64,
+    out_d: *mut u64,
+) -> i32 {
+    if out_n.is_null() || out_d.is_null() {
+        return 1;
+    }
+    let mediant = Fraction::new(a_n, a_d)
+        .and_then(|a| Fraction::new(b_n, b_d).and_then(|b| a.mediant(&b)));
+    match mediant {
+        Ok(m) => {
+            *out_n = m.numerator();
+            *out_d = m.denominator();
+            0
+        }
+        Err(_) => 1,
+    }
+}
+
+/// Returns the decimal value of `num/den`, or NaN if `den` is zero.
+#[no_mangle]
+pub extern "C" fn fraction_value(num: u64, den: u64) -> f64 {
+    Fraction::new(num, den).map_or(f64::NAN, |f| f.value())
+}
@@ -0,0 +1,1169 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::number_theory::{gcd, lcm};
+use crate::{
+    farey, farey_all_closer, farey_bounded, farey_neighbors, farey_rank, stern_diatomic_index,
+    ContinuedFraction, Fraction128,
+};
+
+/// A fraction represented as numerator/denominator.
+///
+/// Fractions are the building blocks of Farey sequences. In the context of this
+/// algorithm, we maintain two fractions (left and right bounds) and repeatedly
+/// compute their mediant to converge on a target value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fraction {
+    numerator: u64,
+    denominator: u64,
+}
+
+/// Errors produced when constructing or approximating fractions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FractionError {
+    /// A denominator of zero was given or produced.
+    DivByZero,
+    /// An argument was outside the domain of the operation.
+    InvalidInput,
+    /// A numerator or denominator would not fit in `u64`.
+    Overflow,
+    /// A string could not be parsed.
+    Parse(ParseFractionError),
+}
+
+impl fmt::Display for FractionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DivByZero => write!(f, "division by zero: denominator cannot be zero"),
+            Self::InvalidInput => write!(f, "invalid input"),
+            Self::Overflow => write!(f, "overflow: result does not fit in u64"),
+            Self::Parse(e) => write!(f, "parse error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for FractionError {}
+
+/// Why a string could not be parsed as a number or fraction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseFractionError {
+    /// The string was empty, or held no digits.
+    Empty,
+    /// The string contained a character that does not belong there.
+    InvalidCharacter(char),
+}
+
+impl fmt::Display for ParseFractionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "no digits to parse"),
+            Self::InvalidCharacter(c) => write!(f, "unexpected character {c:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseFractionError {}
+
+impl From<ParseFractionError> for FractionError {
+    fn from(e: ParseFractionError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+impl Fraction {
+    pub fn new(numerator: u64, denominator: u64) -> Result<Self, FractionError> {
+        if denominator == 0 {
+            Err(FractionError::DivByZero)
+        } else {
+            Ok(Self { numerator, denominator })
+        }
+    }
+
+    /// Builds a fraction from the results of checked arithmetic, mapping a
+    /// `None` component to `Overflow`.
+    pub(crate) fn from_checked(
+        numerator: Option<u64>,
+        denominator: Option<u64>,
+    ) -> Result<Self, FractionError> {
+        match numerator.zip(denominator) {
+            Some((numerator, denominator)) => Self::new(numerator, denominator),
+            None => Err(FractionError::Overflow),
+        }
+    }
+
+    /// Like [`Fraction::new`], but divides out common factors: 6/4 becomes 3/2
+    /// and 0/5 becomes 0/1.
+    pub fn new_reduced(numerator: u64, denominator: u64) -> Result<Self, FractionError> {
+        Self::new(numerator, denominator).map(|f| f.reduce())
+    }
+
+    /// Returns the equivalent fraction in lowest terms.
+    pub fn reduce(&self) -> Self {
+        let divisor = gcd(self.numerator, self.denominator);
+        Self {
+            numerator: self.numerator / divisor,
+            denominator: self.denominator / divisor,
+        }
+    }
+
+    /// Multiplies both components by `factor`: the same value with larger
+    /// components, undoing [`Fraction::reduce`].
+    ///
+    /// Returns `Overflow` if either product exceeds `u64::MAX`, and `DivByZero`
+    /// for a factor of zero.
+    pub fn scale(&self, factor: u64) -> Result<Self, FractionError> {
+        let numerator = self.numerator.checked_mul(factor);
+        let denominator = self.denominator.checked_mul(factor);
+        Self::from_checked(numerator, denominator)
+    }
+
+    /// Builds a fraction from `(numerator, denominator)` parts; the same as
+    /// [`Fraction::new`], and the inverse of [`Fraction::into_parts`].
+    pub fn from_parts(numerator: u64, denominator: u64) -> Result<Self, FractionError> {
+        Self::new(numerator, denominator)
+    }
+
+    /// Returns `(numerator, denominator)`, for `let (n, d) = frac.into_parts()`.
+    pub fn into_parts(self) -> (u64, u64) {
+        (self.numerator, self.denominator)
+    }
+
+    pub fn numerator(&self) -> u64 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> u64 {
+        self.denominator
+    }
+
+    /// Returns the decimal value of this fraction.
+    pub fn value(&self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    /// Returns [`Fraction::value`] together with its rounding error
+    /// |p/q - value|.
+    ///
+    /// With value = m * 2^e exactly, the error is |p - q m 2^e| / q, and the
+    /// difference in the numerator is computed exactly in integers. Its terms
+    /// can exceed `u128`, but value is within a few ulps of p/q, so the
+    /// difference itself is below q * 2^max(e, 0) and the wrapping `u128`
+    /// arithmetic recovers it exactly; only the final division rounds. 1/2
+    /// has no error, 1/3 about 1.85e-17.
+    pub fn value_with_rounding_error(&self) -> (f64, f64) {
+        let value = self.value();
+        let (mantissa, exponent) = decompose(value);
+        let (p, q) = (self.numerator as u128, self.denominator as u128);
+        let qm = q.wrapping_mul(mantissa as u128);
+        let difference = if exponent >= 0 {
+            p.wrapping_sub(qm.wrapping_shl(exponent as u32))
+        } else {
+            p.wrapping_shl(exponent.unsigned_abs()).wrapping_sub(qm)
+        };
+        let difference = (difference as i128).unsigned_abs() as f64;
+        (value, difference / q as f64 * 2f64.powi(exponent.min(0)))
+    }
+
+    /// Returns the exact rational value of an `f64`.
+    ///
+    /// Every finite `f64` is m * 2^e for an integer mantissa m < 2^53, so
+    /// 0.1 is exactly 3602879701896397 / 36028797018963968 (= 2^55). The
+    /// result is reduced and needs [`Fraction128`]: already 0.1 has a
+    /// denominator above 2^52, and large values need wide numerators.
+    ///
+    /// Returns `InvalidInput` for NaN, infinities and negative numbers, and
+    /// `Overflow` if either component exceeds `u128`: the numerator for values
+    /// of 2^128 or more, and the denominator, a power of two after reduction,
+    /// when it exceeds 2^127, i.e. when x is not a multiple of 2^-127. So
+    /// 2^-127 fits but 3 * 2^-128 does not.
+    pub fn from_f64_exact(x: f64) -> Result<Fraction128, FractionError> {
+        if !x.is_finite() || (x.is_sign_negative() && x != 0.0) {
+            return Err(FractionError::InvalidInput);
+        }
+        let (mantissa, exponent) = decompose(x);
+        if mantissa == 0 {
+            return Fraction128::new(0, 1);
+        }
+
+        // Strip factors of two so the result comes out reduced
+        let shift = mantissa.trailing_zeros();
+        let (mantissa, exponent) = ((mantissa >> shift) as u128, exponent + shift as i32);
+        if exponent >= 0 {
+            let numerator = mantissa
+                .checked_mul(1u128.checked_shl(exponent as u32).ok_or(FractionError::Overflow)?)
+                .ok_or(FractionError::Overflow)?;
+            Fraction128::new(numerator, 1)
+        } else {
+            let denominator = 1u128.checked_shl(-exponent as u32).ok_or(FractionError::Overflow)?;
+            Fraction128::new(mantissa, denominator)
+        }
+    }
+
+    /// Returns the decimal value of this fraction; the same as [`Fraction::value`].
+    pub fn to_f64(&self) -> f64 {
+        self.value()
+    }
+
+    /// Returns the decimal value of this fraction in single precision.
+    pub fn to_f32(&self) -> f32 {
+        self.numerator as f32 / self.denominator as f32
+    }
+
+    /// Approximates a single-precision float as a fraction.
+    ///
+    /// An `f32` carries only 24 bits of mantissa, so denominators are capped
+    /// at `u32::MAX`: going further would only chase digits the input never
+    /// had. The result can therefore be coarser than approximating the same
+    /// number given as an `f64`.
+    pub fn from_f32(x: f32) -> Result<Self, FractionError> {
+        farey_bounded(x as f64, u32::MAX as u64)
+    }
+
+    /// Returns the convergents of `real` with denominator at most
+    /// `max_denominator`, in order.
+    ///
+    /// Each is closer to `real` than the one before, and no fraction with a
+    /// smaller denominator is closer than any of them.
+    pub fn convergents_up_to_denom(real: f64, max_denominator: u64) -> Result<Vec<Fraction>, FractionError> {
+        let cf = ContinuedFraction::from_f64(real)?;
+        Ok(cf
+            .convergents()
+            .take_while(|c| c.denominator() <= max_denominator)
+            .collect())
+    }
+
+    /// Returns the denominator ladder of `real`: `(q, p/q, error)` for each
+    /// denominator q up to `max_denominator` whose nearest fraction p/q beats
+    /// every smaller denominator.
+    ///
+    /// The same records as [`farey_all_closer`], keyed by denominator. Besides
+    /// the convergents it contains the semiconvergents that improve on them,
+    /// such as 13/4 on the way from 3/1 to 22/7 for π.
+    pub fn denominator_ladder(real: f64, max_denominator: u64) -> Result<Vec<(u64, Fraction, f64)>, FractionError> {
+        Ok(farey_all_closer(real, max_denominator)?
+            .into_iter()
+            .map(|(frac, error)| (frac.denominator, frac, error))
+            .collect())
+    }
+
+    /// Returns the smallest denominator q for which some p/q is within
+    /// `target_error` of `real`, i.e. |real - p/q| < target_error.
+    ///
+    /// By Dirichlet's approximation theorem q <= ceil(1/target_error) always
+    /// works, but the least q is usually far smaller. That q belongs to a
+    /// best approximation, which is a convergent or a semiconvergent, so this
+    /// walks the runs of semiconvergents (p_{k-1} + j p_k)/(q_{k-1} + j q_k),
+    /// j = 1..=a_{k+1}, finding the first run that ends close enough and
+    /// binary searching it for the smallest j that is.
+    ///
+    /// Errors are measured in `f64`, so a target finer than `f64` resolves is
+    /// usually met by the last convergent, whose error rounds to zero.
+    /// Returns `InvalidInput` unless `target_error` is positive, or if not even
+    /// that convergent is close enough.
+    pub fn denominator_bound_for_error(real: f64, target_error: f64) -> Result<u64, FractionError> {
+        if target_error.is_nan() || target_error <= 0.0 {
+            return Err(FractionError::InvalidInput);
+        }
+        let cf = ContinuedFraction::from_f64(real)?;
+        let real = crate::validate_input(real)?;
+        let close_enough = |(p, q): (u64, u64)| (real - p as f64 / q as f64).abs() < target_error;
+
+        let terms = cf.terms();
+        // p_{-1}/q_{-1} = 1/0 and p_0/q_0 = a0/1
+        let (mut prev, mut current) = ((1u64, 0u64), (terms[0], 1u64));
+        if close_enough(current) {
+            return Ok(1);
+        }
+        for &term in &terms[1..] {
+            let semiconvergent = |j: u64| {
+                let p = j.checked_mul(current.0).and_then(|p| p.checked_add(prev.0));
+                let q = j.checked_mul(current.1).and_then(|q| q.checked_add(prev.1));
+                p.zip(q).ok_or(FractionError::Overflow)
+            };
+            let next = semiconvergent(term)?;
+            if close_enough(next) {
+                // The run closes in on the target monotonically
+                let (mut lo, mut hi) = (1, term);
+                while lo < hi {
+                    let mid = lo + (hi - lo) / 2;
+                    if close_enough(semiconvergent(mid)?) {
+                        hi = mid;
+                    } else {
+                        lo = mid + 1;
+                    }
+                }
+                return Ok(semiconvergent(lo)?.1);
+            }
+            (prev, current) = (current, next);
+        }
+        Err(FractionError::InvalidInput)
+    }
+
+    /// Returns the value of this fraction as a percentage, e.g. 1/4 -> 25.0.
+    pub fn to_percent(&self) -> f64 {
+        self.value() * 100.0
+    }
+
+    /// Approximates a percentage as a fraction, e.g. 25.0 -> 1/4.
+    pub fn from_percent(p: f64) -> Result<Self, FractionError> {
+        farey(p / 100.0)
+    }
+
+    /// Approximates an angle as a fraction of a full turn, e.g. 90.0 -> 1/4
+    /// and 30.0 -> 1/12.
+    ///
+    /// Uses [`farey_bounded`] with denominators up to 360, so every whole
+    /// number of degrees is exact; angles of a full turn or more give
+    /// fractions of 1 or more.
+    pub fn from_angle_degrees(degrees: f64) -> Result<Self, FractionError> {
+        farey_bounded(degrees / 360.0, 360)
+    }
+
+    /// Returns this fraction of a full turn in degrees, e.g. 1/3 -> 120.0.
+    pub fn to_angle_degrees(&self) -> f64 {
+        self.value() * 360.0
+    }
+
+    /// Builds a time signature such as 3/4 or 6/8, kept unreduced since 6/8
+    /// and 3/4 are different meters.
+    ///
+    /// Returns `DivByZero` for a zero denominator, and `InvalidInput` unless
+    /// there is at least one beat and the denominator is a power of two up
+    /// to 64.
+    pub fn from_time_signature(numerator: u8, denominator: u8) -> Result<Self, FractionError> {
+        let fraction = Self::new(numerator as u64, denominator as u64)?;
+        if !fraction.is_valid_time_signature() {
+            return Err(FractionError::InvalidInput);
+        }
+        Ok(fraction)
+    }
+
+    /// Returns true if this fraction, unreduced, is a time signature that
+    /// [`Fraction::from_time_signature`] accepts.
+    pub fn is_valid_time_signature(&self) -> bool {
+        (1..=u8::MAX as u64).contains(&self.numerator)
+            && self.denominator.is_power_of_two()
+            && self.denominator <= 64
+    }
+
+    /// Returns the fraction in ratio notation, e.g. `"16:9"`.
+    pub fn as_ratio_str(&self) -> String {
+        format!("{}:{}", self.numerator, self.denominator)
+    }
+
+    /// Parses ratio notation such as `"16:9"`, the inverse of
+    /// [`Fraction::as_ratio_str`]. Whitespace around either part is ignored.
+    ///
+    /// Returns a [`ParseFractionError`] (as `FractionError::Parse`) if a part
+    /// is missing or is not a number, `Overflow` if one exceeds `u64::MAX`,
+    /// and `DivByZero` for a zero second part.
+    pub fn from_ratio_str(s: &str) -> Result<Self, FractionError> {
+        let (numerator, denominator) = s.split_once(':').unwrap_or((s, ""));
+        Self::new(parse_u64(numerator)?, parse_u64(denominator)?)
+    }
+
+    /// Returns a Graphviz DOT node statement: `"3_4" [label="3/4"];`.
+    ///
+    /// The node ID uses an underscore, so every fraction gets a distinct,
+    /// quoting-safe ID that [`Fraction::to_graphviz_edge`] refers to.
+    pub fn to_graphviz_node(&self) -> String {
+        let (n, d) = (self.numerator, self.denominator);
+        format!(r#""{n}_{d}" [label="{n}/{d}"];"#)
+    }
+
+    /// Returns a Graphviz DOT edge statement from `a` to `b` for a `digraph`,
+    /// e.g. `"1_2" -> "1_3" [label="L"];`, or without the attribute list if
+    /// `label` is empty. Quotes and backslashes in the label are escaped.
+    pub fn to_graphviz_edge(a: Fraction, b: Fraction, label: &str) -> String {
+        let edge = format!(
+            r#""{}_{}" -> "{}_{}""#,
+            a.numerator, a.denominator, b.numerator, b.denominator
+        );
+        if label.is_empty() {
+            format!("{edge};")
+        } else {
+            let label = label.replace('\\', "\\\\").replace('"', "\\\"");
+            format!(r#"{edge} [label="{label}"];"#)
+        }
+    }
+
+    /// Returns the fraction as HTML for CSS styling:
+    /// `<span class="fraction"><sup>3</sup><span>/</span><sub>4</sub></span>`.
+    ///
+    /// Without styling this reads as a slashed fraction. To stack it, hide the
+    /// slash and put the parts on separate lines, e.g.
+    ///
+    /// ```css
+    /// .fraction { display: inline-flex; flex-direction: column; text-align: center; vertical-align: middle; }
+    /// .fraction > sup, .fraction > sub { font-size: inherit; vertical-align: baseline; }
+    /// .fraction > sup { border-bottom: 1px solid; }
+    /// .fraction > span { display: none; }
+    /// ```
+    pub fn to_html(&self) -> String {
+        format!(
+            r#"<span class="fraction"><sup>{}</sup><span>/</span><sub>{}</sub></span>"#,
+            self.numerator, self.denominator
+        )
+    }
+
+    /// Returns the fraction as MathML: `<math><mfrac><mn>3</mn><mn>4</mn></mfrac></math>`.
+    pub fn to_html_math(&self) -> String {
+        format!(
+            "<math><mfrac><mn>{}</mn><mn>{}</mn></mfrac></math>",
+            self.numerator, self.denominator
+        )
+    }
+
+    /// Returns the fraction in Unicode, e.g. 22/7 as `²²⁄₇`.
+    ///
+    /// Uses the precomposed character from [`Fraction::to_unicode_vulgar`]
+    /// where there is one, and otherwise superscript digits, the fraction
+    /// slash U+2044 and subscript digits.
+    pub fn to_unicode_fraction(&self) -> String {
+        if let Some(vulgar) = self.to_unicode_vulgar() {
+            return vulgar.to_string();
+        }
+        const SUPERSCRIPTS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+        const SUBSCRIPTS: [char; 10] = ['₀', '₁', '₂', '₃', '₄', '₅', '₆', '₇', '₈', '₉'];
+        let digits = |n: u64, table: &[char; 10]| -> String {
+            n.to_string().bytes().map(|b| table[(b - b'0') as usize]).collect()
+        };
+        format!(
+            "{}\u{2044}{}",
+            digits(self.numerator, &SUPERSCRIPTS),
+            digits(self.denominator, &SUBSCRIPTS)
+        )
+    }
+
+    /// Returns the precomposed Unicode vulgar fraction for this exact
+    /// numerator and denominator, such as `½` or `⅞`, if there is one.
+    ///
+    /// The components are taken as written, so 2/4 has none.
+    pub fn to_unicode_vulgar(&self) -> Option<char> {
+        let vulgar = match (self.numerator, self.denominator) {
+            (0, 3) => '↉',
+            (1, 2) => '½',
+            (1, 3) => '⅓',
+            (2, 3) => '⅔',
+            (1, 4) => '¼',
+            (3, 4) => '¾',
+            (1, 5) => '⅕',
+            (2, 5) => '⅖',
+            (3, 5) => '⅗',
+            (4, 5) => '⅘',
+            (1, 6) => '⅙',
+            (5, 6) => '⅚',
+            (1, 7) => '⅐',
+            (1, 8) => '⅛',
+            (3, 8) => '⅜',
+            (5, 8) => '⅝',
+            (7, 8) => '⅞',
+            (1, 9) => '⅑',
+            (1, 10) => '⅒',
+            _ => return None,
+        };
+        Some(vulgar)
+    }
+
+    /// Counts the reduced fractions strictly between `lo` and `hi` with
+    /// denominator at most `max_denominator`, within the Farey range [0, 1].
+    ///
+    /// The difference of two [`farey_rank`]s, less one if `hi` itself is
+    /// counted by its rank, so [0/1, 1/1] holds |F_n| - 2. Runs in
+    /// O(n log n). Returns 0 unless `lo < hi`.
+    pub fn count_fractions_between(lo: Fraction, hi: Fraction, max_denominator: u64) -> u64 {
+        if Self::cross_product(lo, hi) >= 0 {
+            return 0;
+        }
+        let top = hi.reduce();
+        let hi_is_term = top.denominator <= max_denominator && top.numerator <= top.denominator;
+        (farey_rank(hi, max_denominator) - farey_rank(lo, max_denominator))
+            .saturating_sub(hi_is_term as u64)
+    }
+
+    /// Returns the mediants of this fraction with its neighbors in F_n:
+    /// (mediant(left, self), mediant(self, right)).
+    ///
+    /// These are the next fractions to squeeze in beside it as n grows. With
+    /// neighbors a/b and c/d of p/q, the left one enters at F_{b+q} and the
+    /// right one at F_{q+d}: for 1/2 in F_3, between 1/3 and 2/3, they are 2/5
+    /// and 3/5, both in F_5. Errors as for
+    /// [`neighbors_in_farey`](Fraction::neighbors_in_farey).
+    pub fn neighbor_mediants(&self, n: u64) -> Result<(Fraction, Fraction), FractionError> {
+        let (left, right) = self.neighbors_in_farey(n)?;
+        Ok((left.mediant(self)?, self.mediant(&right)?))
+    }
+
+    /// Returns true if this is a unit fraction 1/n in lowest terms, so 2/6 is
+    /// one and 1/1 counts as well.
+    pub fn is_unit(&self) -> bool {
+        // The denominator is nonzero, so a zero numerator never divides it
+        self.denominator.is_multiple_of(self.numerator)
+    }
+
+    /// Returns true if 1/(n+1) < self < 1/n.
+    ///
+    /// Every non-unit fraction in F_n falls into exactly one such slot between
+    /// consecutive unit fractions, which is what sieve-style Farey
+    /// constructions partition on. Compares by cross-multiplying in `u128`, so
+    /// no precision is lost for large components.
+    pub fn is_between_unit_fractions(&self, n: u64) -> bool {
+        let p = self.numerator as u128;
+        let q = self.denominator as u128;
+        let n = n as u128;
+        // 1/(n+1) < p/q  <=>  q < p(n+1),  and  p/q < 1/n  <=>  pn < q
+        q < p * (n + 1) && p * n < q
+    }
+
+    /// Returns the determinant ps - qr of a = p/q and b = r/s.
+    ///
+    /// Its sign orders the two (negative when a < b), and neighbors in a
+    /// Farey sequence have a determinant of -1. The products are taken in
+    /// `u128`; a difference beyond the `i128` range, possible only when
+    /// components exceed 2^63, saturates to `i128::MIN` or `i128::MAX`, which
+    /// keeps the sign and every value of magnitude 1 exact.
+    pub fn cross_product(a: Fraction, b: Fraction) -> i128 {
+        let ps = a.numerator as u128 * b.denominator as u128;
+        let qr = a.denominator as u128 * b.numerator as u128;
+        if ps >= qr {
+            i128::try_from(ps - qr).unwrap_or(i128::MAX)
+        } else {
+            i128::try_from(qr - ps).map_or(i128::MIN, |d| -d)
+        }
+    }
+
+    /// Compares this fraction with `real` exactly, without rounding either
+    /// side to `f64`.
+    ///
+    /// `real` is taken as its exact value m * 2^e (see
+    /// [`Fraction::from_f64_exact`]) and cross-multiplied in `u128`; shifts
+    /// too large for that are decided by bit length alone. So 1/10 is
+    /// `Less` than `0.1`, whose value is slightly above a tenth, even though
+    /// `value()` rounds it to exactly `0.1`. Negative numbers and -∞ are
+    /// below every fraction and +∞ above; NaN counts as above everything, as
+    /// positive NaN does in [`f64::total_cmp`].
+    pub fn cmp_with_real(&self, real: f64) -> Ordering {
+        if real.is_nan() || real == f64::INFINITY {
+            return Ordering::Less;
+        }
+        if real < 0.0 || real == f64::NEG_INFINITY {
+            return Ordering::Greater;
+        }
+        let (mantissa, exponent) = decompose(real);
+        let (p, q) = (self.numerator as u128, self.denominator as u128);
+        if mantissa == 0 || p == 0 {
+            return p.cmp(&(mantissa as u128));
+        }
+        // Compare p/q with m * 2^e as p * 2^-e against q * m
+        let qm = q * mantissa as u128;
+        if exponent >= 0 {
+            // q * m * 2^e has at least 64 bits, more than p can have
+            if qm.ilog2() + exponent as u32 >= 64 {
+                return Ordering::Less;
+            }
+            p.cmp(&(qm << exponent))
+        } else {
+            let shift = exponent.unsigned_abs();
+            // p * 2^-e has at least 128 bits, more than q * m < 2^117 can have
+            if p.ilog2() + shift >= 127 {
+                return Ordering::Greater;
+            }
+            (p << shift).cmp(&qm)
+        }
+    }
+
+    /// Returns the smaller of `a` and `b` by value, comparing exactly with
+    /// [`Fraction::cross_product`]. Returns `a` if they are equal, like
+    /// [`std::cmp::min`].
+    #[inline]
+    pub fn min(a: Fraction, b: Fraction) -> Fraction {
+        if Self::cross_product(a, b) <= 0 { a } else { b }
+    }
+
+    /// Returns the larger of `a` and `b` by value, comparing exactly with
+    /// [`Fraction::cross_product`]. Returns `b` if they are equal, like
+    /// [`std::cmp::max`].
+    #[inline]
+    pub fn max(a: Fraction, b: Fraction) -> Fraction {
+        if Self::cross_product(a, b) > 0 { a } else { b }
+    }
+
+    /// Restricts this fraction to the interval [lo, hi] by value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lo > hi`, like [`Ord::clamp`].
+    pub fn clamp(&self, lo: Fraction, hi: Fraction) -> Fraction {
+        assert!(Self::cross_product(lo, hi) <= 0, "clamp bounds out of order");
+        Self::min(Self::max(*self, lo), hi)
+    }
+
+    /// Returns true if this fraction lies in the Stern-Brocot subtree rooted at
+    /// mediant(lo, hi), i.e. lo < self < hi, compared exactly.
+    ///
+    /// The subtree between two Farey neighbors (see
+    /// [`Fraction::is_farey_neighbor`]) holds exactly the rationals strictly
+    /// between them, so its root mediant(lo, hi) is inside and the bounds
+    /// themselves are not. For bounds that are not neighbors this is just the
+    /// interval test.
+    pub fn is_in_stern_brocot_subtree(&self, lo: Fraction, hi: Fraction) -> bool {
+        Self::cross_product(lo, *self) < 0 && Self::cross_product(*self, hi) < 0
+    }
+
+    /// Returns true if `self` and `other` are Farey neighbors: |ps - qr| = 1
+    /// for p/q and r/s. In [0, 1] that makes them adjacent in F_n for
+    /// max(q, s) <= n < q + s.
+    ///
+    /// Neighbors are automatically in lowest terms, since a common factor of
+    /// p and q would divide ps - qr. Their mediant lies strictly between them
+    /// and is a Farey neighbor of both, which is how the Stern-Brocot tree
+    /// grows. See [`Fraction::cross_product`].
+    pub fn is_farey_neighbor(&self, other: Fraction) -> bool {
+        Self::cross_product(*self, other).unsigned_abs() == 1
+    }
+
+    /// Returns the terms immediately before and after this fraction in F_n.
+    ///
+    /// See [`farey_neighbors`].
+    pub fn neighbors_in_farey(&self, n: u64) -> Result<(Fraction, Fraction), FractionError> {
+        farey_neighbors(*self, n)
+    }
+
+    /// Returns the length of the path from the root 1/1 to this fraction in
+    /// the Stern-Brocot tree, a measure of how complicated the fraction is.
+    ///
+    /// If p/q = [a0; a1, ..., an], the path is R^a0 L^a1 ... with the last run
+    /// one short, so its length is a0 + a1 + ... + an - 1: 3/4 = [0; 1, 3] has
+    /// length 3, while the Fibonacci ratio 144/89 = [1; 1, 1, ..., 2] has 10.
+    /// The sum of the partial quotients never exceeds max(p, q), so this
+    /// cannot overflow. The fraction is reduced first.
+    ///
+    /// Returns `InvalidInput` for zero, which is not in the tree.
+    pub fn stern_brocot_path_length(&self) -> Result<u64, FractionError> {
+        if self.numerator == 0 {
+            return Err(FractionError::InvalidInput);
+        }
+        let cf = ContinuedFraction::from_fraction(self.reduce());
+        Ok(cf.terms().iter().sum::<u64>() - 1)
+    }
+
+    /// Returns this fraction's position n in the Calkin-Wilf sequence, so that
+    /// s(n) is the numerator and s(n+1) the denominator.
+    ///
+    /// Returns `None` if the fraction is not in lowest terms, since only the
+    /// reduced form appears in the sequence, or if n does not fit in `u64`.
+    /// See [`stern_diatomic_index`].
+    pub fn to_stern_diatomic_index(&self) -> Option<u64> {
+        if gcd(self.numerator, self.denominator) != 1 {
+            return None;
+        }
+        stern_diatomic_index(*self).ok()
+    }
+
+    /// Computes the mediant of two fractions.
+    ///
+    /// The mediant of a/b and c/d is (a+c)/(b+d). This is NOT the arithmetic mean,
+    /// but rather "Farey addition". The mediant has a key property: if a/b < c/d,
+    /// then a/b < mediant < c/d. This property enables binary search over rationals.
+    ///
+    /// Example: mediant of 1/2 and 1/3 is (1+1)/(2+3) = 2/5
+    ///
+    /// Returns `Overflow` if either sum exceeds `u64::MAX`.
+    pub fn mediant(&self, other: &Fraction) -> Result<Self, FractionError> {
+        let numerator = self.numerator.checked_add(other.numerator);
+        let denominator = self.denominator.checked_add(other.denominator);
+        Self::from_checked(numerator, denominator)
+    }
+
+    /// Computes the mediant of any number of fractions:
+    /// (a1 + a2 + ... + an) / (b1 + b2 + ... + bn).
+    ///
+    /// The result always lies between the smallest and largest operand, and
+    /// weights each by its denominator, like pooling measurements of
+    /// successes a_i out of trials b_i.
+    ///
+    /// Returns `InvalidInput` for an empty slice and `Overflow` if either sum
+    /// exceeds `u64::MAX`.
+    pub fn mediant_of_slice(fractions: &[Fraction]) -> Result<Self, FractionError> {
+        let (first, rest) = fractions.split_first().ok_or(FractionError::InvalidInput)?;
+        rest.iter().try_fold(*first, |sum, frac| sum.mediant(frac))
+    }
+}
+
+/// Parses a whole number of decimal digits, ignoring surrounding whitespace.
+fn parse_u64(s: &str) -> Result<u64, FractionError> {
+    let s = s.trim();
+    if let Some(c) = s.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(ParseFractionError::InvalidCharacter(c).into());
+    }
+    if s.is_empty() {
+        return Err(ParseFractionError::Empty.into());
+    }
+    // Only digits are left, so the parse can fail only by overflowing
+    s.parse().map_err(|_| FractionError::Overflow)
+}
+
+impl TryFrom<(u64, u64)> for Fraction {
+    type Error = FractionError;
+
+    /// Builds a fraction from `(numerator, denominator)`, failing with
+    /// `DivByZero` for a zero denominator.
+    fn try_from((numerator, denominator): (u64, u64)) -> Result<Self, FractionError> {
+        Self::new(numerator, denominator)
+    }
+}
+
+/// Splits a non-negative finite `x` into m and e with x = m * 2^e exactly,
+/// m < 2^53.
+pub(crate) fn decompose(x: f64) -> (u64, i32) {
+    let bits = x.to_bits();
+    let biased_exponent = ((bits >> 52) & 0x7ff) as i32;
+    let fraction_bits = bits & ((1 << 52) - 1);
+    // Subnormals have no implicit leading bit and the minimum exponent
+    if biased_exponent == 0 {
+        (fraction_bits, -1074)
+    } else {
+        (fraction_bits | 1 << 52, biased_exponent - 1075)
+    }
+}
+
+/// Scales `a` and `b` to the least common multiple of their denominators.
+///
+/// Returns `Overflow` if the common denominator or a scaled numerator exceeds
+/// `u64::MAX`.
+pub fn common_denominator(a: Fraction, b: Fraction) -> Result<(Fraction, Fraction), FractionError> {
+    let denominator = lcm(a.denominator, b.denominator).ok_or(FractionError::Overflow)?;
+    Ok((
+        a.scale(denominator / a.denominator)?,
+        b.scale(denominator / b.denominator)?,
+    ))
+}
+
+/// Format the fraction as follows:
+///               27450985
+/// 0.33333339 ≈ ----------
+///               82352941
+///
+/// The value is shown to the formatter's precision if one is given (`{:.3}`),
+/// and otherwise to up to 15 places with trailing zeros dropped. A width
+/// (`{:30}`) shifts the art lines right until the block is that wide.
+///
+/// The alternate form `{:#}` puts the value on its own line and centers the
+/// fraction bar below its decimal point:
+///
+/// ```text
+/// 17636.571428571427532
+///   123456
+///  --------
+///     7
+/// ```
+impl fmt::Display for Fraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_fraction_art(f, self.value(), self.numerator as u128, self.denominator as u128)
+    }
+}
+
+/// Formats the value in scientific notation, e.g. `3.75e-1` for 3/8.
+impl fmt::LowerExp for Fraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerExp::fmt(&self.value(), f)
+    }
+}
+
+/// Formats the value in scientific notation, e.g. `3.75E-1` for 3/8.
+impl fmt::UpperExp for Fraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::UpperExp::fmt(&self.value(), f)
+    }
+}
+
+/// Formats both components in binary, e.g. `11/1000` for 3/8, or
+/// `0b11/0b1000` with `{:#b}`.
+impl fmt::Binary for Fraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "{:#b}/{:#b}", self.numerator, self.denominator)
+        } else {
+            write!(f, "{:b}/{:b}", self.numerator, self.denominator)
+        }
+    }
+}
+
+/// Writes the ASCII-art layout shared by the fraction types' `Display` impls.
+pub(crate) fn write_fraction_art(
+    f: &mut fmt::Formatter<'_>,
+    value: f64,
+    numerator: u128,
+    denominator: u128,
+) -> fmt::Result {
+    let value_str = match f.precision() {
+        Some(precision) => format!("{value:.precision$}"),
+        None => format!("{value:.15}")
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string(),
+    };
+
+    let frac_width = numerator.max(denominator).to_string().len();
+    let sep_width = frac_width + 2;
+    if f.alternate() {
+        return write_centered_art(f, &value_str, numerator, denominator, sep_width);
+    }
+    // Right-align fractions to end at the same column as the separator
+    let pad = value_str.len() + 3 + sep_width;
+    let indent = " ".repeat(f.width().unwrap_or(0).saturating_sub(pad));
+
+    write!(
+        f,
+        "\n{indent}{numerator:>pad$}\n{indent}{value_str} ≈ {:-<sep_width$}\n{indent}{denominator:>pad$}\n\n$ {value_str} ≈ frac({numerator},{denominator}) $",
+        ""
+    )
+}
+
+/// The `{:#}` layout: the value on its own line with the fraction below it,
+/// the bar centered under the decimal point.
+fn write_centered_art(
+    f: &mut fmt::Formatter<'_>,
+    value_str: &str,
+    numerator: u128,
+    denominator: u128,
+    sep_width: usize,
+) -> fmt::Result {
+    let point = value_str.find('.').unwrap_or(value_str.len());
+    // The column both the decimal point and the middle of the bar sit in
+    let center = point.max(sep_width / 2);
+    let value_indent = " ".repeat(center - point);
+    let bar_indent = " ".repeat(center - sep_width / 2);
+    let block_width = (value_indent.len() + value_str.len()).max(bar_indent.len() + sep_width);
+    let indent = " ".repeat(f.width().unwrap_or(0).saturating_sub(block_width));
+    // Center over the bar by padding on the left only, so no line ends in spaces
+    let centered = |n: u128| {
+        let digits = n.to_string();
+        format!("{}{digits}", " ".repeat((sep_width - digits.len()) / 2))
+    };
+
+    write!(
+        f,
+        "\n{indent}{value_indent}{value_str}\n{indent}{bar_indent}{}\n{indent}{bar_indent}{:-<sep_width$}\n{indent}{bar_indent}{}\n\n$ {value_str} ≈ frac({numerator},{denominator}) $",
+        centered(numerator),
+        "",
+        centered(denominator)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn frac(numerator: u64, denominator: u64) -> Fraction {
+        Fraction::new(numerator, denominator).unwrap()
+    }
+
+    #[test]
+    fn percent_round_trip() {
+        assert_eq!(frac(1, 4).to_percent(), 25.0);
+        assert_eq!(frac(3, 2).to_percent(), 150.0);
+        assert_eq!(Fraction::from_percent(25.0), Ok(frac(1, 4)));
+        assert_eq!(Fraction::from_percent(-5.0), Err(FractionError::InvalidInput));
+    }
+
+    #[test]
+    fn between_unit_fractions_boundaries() {
+        assert!(frac(2, 5).is_between_unit_fractions(2));
+        assert!(frac(4, 10).is_between_unit_fractions(2));
+        // The unit fractions themselves are excluded
+        assert!(!frac(1, 2).is_between_unit_fractions(2));
+        assert!(!frac(1, 3).is_between_unit_fractions(2));
+        assert!(!frac(0, 1).is_between_unit_fractions(1));
+        // Above 1/1 only the open slot (1, ∞) of n = 0 applies
+        assert!(frac(3, 2).is_between_unit_fractions(0));
+        assert!(!frac(1, 1).is_between_unit_fractions(0));
+        let big = u64::MAX;
+        assert!(frac(2, big).is_between_unit_fractions(big / 2));
+    }
+
+    #[test]
+    fn non_unit_farey_terms_fall_into_one_slot() {
+        for n in 1..=30 {
+            for term in crate::FareySequence::new(n).filter(|t| t.numerator() > 0 && !t.is_unit()) {
+                let slots = (1..=n).filter(|&k| term.is_between_unit_fractions(k)).count();
+                assert_eq!(slots, 1, "{term:?} in F_{n}");
+            }
+        }
+    }
+
+    #[test]
+    fn new_reduced_divides_out_common_factors() {
+        assert_eq!(Fraction::new_reduced(6, 4), Ok(Fraction { numerator: 3, denominator: 2 }));
+        assert_eq!(Fraction::new_reduced(6, 4), Fraction::new(3, 2));
+        assert_eq!(Fraction::new_reduced(0, 5), Ok(Fraction { numerator: 0, denominator: 1 }));
+        assert_eq!(Fraction::new_reduced(7, 0), Err(FractionError::DivByZero));
+        // new keeps the components as given
+        assert_eq!(frac(6, 4).into_parts(), (6, 4));
+    }
+
+    #[test]
+    fn reduce_is_idempotent() {
+        assert_eq!(frac(6, 4).reduce(), frac(3, 2));
+        assert_eq!(frac(3, 2).reduce(), frac(3, 2));
+        assert_eq!(frac(u64::MAX, u64::MAX).reduce(), frac(1, 1));
+        assert_eq!(frac(12, 6).reduce().reduce(), frac(2, 1));
+    }
+
+    #[test]
+    fn f32_round_trip() {
+        for (p, q) in [(1, 2), (3, 4), (1, 3), (22, 7), (5, 1), (0, 1)] {
+            let x = frac(p, q);
+            assert_eq!(x.to_f32(), p as f32 / q as f32);
+            assert_eq!(x.to_f64(), x.value());
+            let back = Fraction::from_f32(x.to_f32()).unwrap();
+            assert_eq!(back.to_f32(), x.to_f32(), "{p}/{q}");
+            assert!(back.denominator() <= u32::MAX as u64);
+        }
+        assert_eq!(Fraction::from_f32(0.75).map(|f| f.reduce()), Ok(frac(3, 4)));
+        assert_eq!(Fraction::from_f32(f32::NAN), Err(FractionError::InvalidInput));
+    }
+
+    #[test]
+    fn stern_diatomic_index_of_the_first_calkin_wilf_terms() {
+        let first: Vec<_> = (1..=7).map(|n| crate::stern_diatomic_fraction(n).unwrap()).collect();
+        assert_eq!(first, [frac(1, 1), frac(1, 2), frac(2, 1), frac(1, 3), frac(3, 2), frac(2, 3), frac(3, 1)]);
+        for n in 1..=20 {
+            let term = crate::stern_diatomic_fraction(n).unwrap();
+            assert_eq!(term.to_stern_diatomic_index(), Some(n), "{term:?}");
+        }
+        assert_eq!(frac(0, 1).to_stern_diatomic_index(), Some(0));
+        assert_eq!(frac(2, 4).to_stern_diatomic_index(), None);
+        assert_eq!(frac(1, 64).to_stern_diatomic_index(), Some(1 << 63));
+        assert_eq!(frac(1, 65).to_stern_diatomic_index(), None);
+    }
+
+    /// Checks that every tag is closed in the order it was opened.
+    fn is_balanced(markup: &str) -> bool {
+        let mut open = Vec::new();
+        let mut rest = markup;
+        while let Some(start) = rest.find('<') {
+            let Some(end) = rest[start..].find('>') else { return false };
+            let tag = &rest[start + 1..start + end];
+            match tag.strip_prefix('/') {
+                Some(name) if open.pop() != Some(name) => return false,
+                Some(_) => {}
+                None => open.push(tag.split(' ').next().unwrap_or(tag)),
+            }
+            rest = &rest[start + end + 1..];
+        }
+        open.is_empty()
+    }
+
+    #[test]
+    fn html_and_mathml_output() {
+        assert_eq!(
+            frac(3, 4).to_html(),
+            r#"<span class="fraction"><sup>3</sup><span>/</span><sub>4</sub></span>"#
+        );
+        assert_eq!(frac(3, 4).to_html_math(), "<math><mfrac><mn>3</mn><mn>4</mn></mfrac></math>");
+        assert_eq!(frac(0, 1).to_html_math(), "<math><mfrac><mn>0</mn><mn>1</mn></mfrac></math>");
+        for f in [frac(0, 1), frac(5, 1), frac(355, 113), frac(u64::MAX, 1)] {
+            assert!(is_balanced(&f.to_html()), "{}", f.to_html());
+            assert!(is_balanced(&f.to_html_math()), "{}", f.to_html_math());
+        }
+        assert!(!is_balanced("<math><mfrac></math></mfrac>"));
+    }
+
+    #[test]
+    fn convergents_up_to_denom_of_pi() {
+        let pi = std::f64::consts::PI;
+        let convergents = Fraction::convergents_up_to_denom(pi, 355).unwrap();
+        assert_eq!(convergents, [frac(3, 1), frac(22, 7), frac(333, 106), frac(355, 113)]);
+        assert_eq!(Fraction::convergents_up_to_denom(pi, 112).unwrap().last(), Some(&frac(333, 106)));
+        assert_eq!(Fraction::convergents_up_to_denom(pi, 0), Ok(Vec::new()));
+        assert_eq!(Fraction::convergents_up_to_denom(-1.0, 10), Err(FractionError::InvalidInput));
+    }
+
+    #[test]
+    fn convergents_up_to_denom_are_best_approximations() {
+        for x in [std::f64::consts::PI, std::f64::consts::E, 0.827, std::f64::consts::SQRT_2] {
+            let error = |f: Fraction| (x - f.value()).abs();
+            let convergents = Fraction::convergents_up_to_denom(x, 10_000).unwrap();
+            assert!(convergents.windows(2).all(|pair| error(pair[1]) < error(pair[0])), "{x}");
+            for convergent in convergents {
+                for q in 1..convergent.denominator() {
+                    let nearest = frac((q as f64 * x).round() as u64, q);
+                    assert!(error(nearest) > error(convergent), "{nearest:?} beats {convergent:?} for {x}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn denominator_ladder_of_pi() {
+        let pi = std::f64::consts::PI;
+        let ladder = Fraction::denominator_ladder(pi, 120).unwrap();
+        let rungs: Vec<_> = ladder.iter().map(|&(q, f, _)| (q, f)).collect();
+        assert_eq!(rungs[..5], [(1, frac(3, 1)), (4, frac(13, 4)), (5, frac(16, 5)), (6, frac(19, 6)), (7, frac(22, 7))]);
+        assert_eq!(rungs.last(), Some(&(113, frac(355, 113))));
+        for &(q, f, error) in &ladder {
+            assert_eq!(f.denominator(), q);
+            assert_eq!(error, (pi - f.value()).abs());
+        }
+        assert!(ladder.windows(2).all(|pair| pair[1].2 < pair[0].2));
+        assert_eq!(Fraction::denominator_ladder(-1.0, 10), Err(FractionError::InvalidInput));
+    }
+
+    proptest! {
+        #[test]
+        fn neighbors_and_their_mediant(turns in prop::collection::vec(any::<bool>(), 0..60)) {
+            // Walk down the Stern-Brocot tree below 0/1 and 1/1; the bounds stay neighbors
+            let (mut lo, mut hi) = (frac(0, 1), frac(1, 1));
+            for go_left in turns {
+                prop_assert!(lo.is_farey_neighbor(hi) && hi.is_farey_neighbor(lo));
+                let mediant = lo.mediant(&hi).unwrap();
+                prop_assert!(Fraction::cross_product(lo, mediant) < 0);
+                prop_assert!(Fraction::cross_product(mediant, hi) < 0);
+                prop_assert!(mediant.is_farey_neighbor(lo) && mediant.is_farey_neighbor(hi));
+                if go_left { hi = mediant } else { lo = mediant }
+            }
+        }
+
+        #[test]
+        fn neighbors_are_reduced(p in 0..u64::MAX, q in 1..u64::MAX, r in 0..u64::MAX, s in 1..u64::MAX) {
+            let (a, b) = (frac(p, q), frac(r, s));
+            prop_assert_eq!(a.is_farey_neighbor(b), b.is_farey_neighbor(a));
+            if a.is_farey_neighbor(b) {
+                prop_assert!(gcd(p, q) == 1 && gcd(r, s) == 1);
+            }
+            let neighbor = (p as u128 * s as u128).abs_diff(q as u128 * r as u128) == 1;
+            prop_assert_eq!(a.is_farey_neighbor(b), neighbor);
+        }
+    }
+
+    #[test]
+    fn neighbor_mediants_enter_next() {
+        assert_eq!(frac(1, 2).neighbor_mediants(3), Ok((frac(2, 5), frac(3, 5))));
+        assert_eq!(frac(1, 3).neighbor_mediants(5), Ok((frac(2, 7), frac(3, 8))));
+        for n in 2..=12 {
+            let inner = crate::FareySequence::new(n).filter(|t| t.numerator() > 0 && t.numerator() < t.denominator());
+            for term in inner {
+                let (left, right) = term.neighbors_in_farey(n).unwrap();
+                let (before, after) = term.neighbor_mediants(n).unwrap();
+                // Each mediant is the first term to appear in its gap, at F_{b+q} and F_{q+d}
+                assert_eq!(term.neighbors_in_farey(before.denominator()).unwrap().0, before);
+                assert_eq!(term.neighbors_in_farey(after.denominator()).unwrap().1, after);
+                assert_eq!(term.neighbors_in_farey(before.denominator() - 1).unwrap().0, left);
+                assert_eq!(term.neighbors_in_farey(after.denominator() - 1).unwrap().1, right);
+            }
+        }
+        assert!(frac(0, 1).neighbor_mediants(5).is_err());
+        assert!(frac(1, 7).neighbor_mediants(5).is_err());
+    }
+
+    #[test]
+    fn count_fractions_between_matches_enumeration() {
+        // Unreduced bounds, bounds past F_n, and one beyond 1/1
+        let bounds = [
+            frac(0, 1), frac(1, 7), frac(2, 8), frac(1, 3), frac(5, 12),
+            frac(1, 2), frac(13, 17), frac(1, 1), frac(3, 2),
+        ];
+        for n in [1, 2, 5, 10, 16] {
+            let terms: Vec<_> = crate::FareySequence::new(n).collect();
+            for lo in bounds {
+                for hi in bounds {
+                    let expected = terms
+                        .iter()
+                        .filter(|&&t| Fraction::cross_product(lo, t) < 0 && Fraction::cross_product(t, hi) < 0)
+                        .count();
+                    let count = Fraction::count_fractions_between(lo, hi, n);
+                    assert_eq!(count, expected as u64, "{lo:?} {hi:?} F_{n}");
+                }
+            }
+        }
+        let all = Fraction::count_fractions_between(frac(0, 1), frac(1, 1), 100);
+        assert_eq!(all, crate::farey_sequence_len(100) - 2);
+    }
+
+    #[test]
+    fn stern_brocot_subtree_membership() {
+        let (lo, hi) = (frac(1, 2), frac(1, 1));
+        for inside in [frac(2, 3), frac(3, 4), frac(3, 5), frac(99, 100), frac(6, 9)] {
+            assert!(inside.is_in_stern_brocot_subtree(lo, hi), "{inside:?}");
+        }
+        for outside in [lo, hi, frac(2, 4), frac(1, 3), frac(3, 2)] {
+            assert!(!outside.is_in_stern_brocot_subtree(lo, hi), "{outside:?}");
+        }
+        // The chain below neighbors 1/3 and 1/2 is exactly the terms of F_12 between them
+        let chain: Vec<_> = crate::mediant_chain(frac(1, 3), frac(1, 2)).max_denominator(12).collect();
+        let between: Vec<_> = crate::FareySequence::new(12)
+            .filter(|f| f.is_in_stern_brocot_subtree(frac(1, 3), frac(1, 2)))
+            .collect();
+        assert_eq!(chain.len(), between.len());
+        assert!(chain.iter().all(|f| between.contains(f)));
+    }
+
+    #[test]
+    fn centered_display_has_no_trailing_spaces() {
+        let art = format!("{:#}", frac(355, 113));
+        assert_eq!(art, "\n 3.141592920353983\n 355\n-----\n 113\n\n$ 3.141592920353983 ≈ frac(355,113) $");
+        let art = format!("{:#.2}", frac(1, 100));
+        assert_eq!(art, "\n 0.01\n  1\n-----\n 100\n\n$ 0.01 ≈ frac(1,100) $");
+        for f in [frac(1, 3), frac(22, 7), frac(1, 1000), frac(123456, 7)] {
+            for width in [0, 40] {
+                let art = format!("{f:#width$}");
+                assert!(art.lines().all(|line| !line.ends_with(' ')), "{art:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn rounding_error_of_small_fractions() {
+        assert_eq!(frac(1, 2).value_with_rounding_error(), (0.5, 0.0));
+        assert_eq!(frac(3, 4).value_with_rounding_error(), (0.75, 0.0));
+        let (value, error) = frac(1, 3).value_with_rounding_error();
+        // 1 - 3v is exactly 2^-54, which mul_add keeps without rounding
+        let exact = (-3.0f64).mul_add(value, 1.0).abs() / 3.0;
+        assert!(error > 0.0);
+        assert_eq!(error, exact);
+        assert!((error - 1.85e-17).abs() < 1e-19);
+    }
+
+    #[test]
+    fn rounding_error_near_u64_max() {
+        // Whole-number values: the error is the distance to the rounded f64
+        for (p, q) in [(u64::MAX, 1), (u64::MAX, 3), (u64::MAX, 5), ((1 << 53) + 1, 1)] {
+            let (value, error) = frac(p, q).value_with_rounding_error();
+            assert_eq!(error, (value as u128).abs_diff((p / q) as u128) as f64, "{p}/{q}");
+        }
+        // Values next to 1/1 round to it, leaving the whole gap as the error
+        let (value, error) = frac(u64::MAX, u64::MAX - 1).value_with_rounding_error();
+        assert_eq!((value, error), (1.0, 1.0 / (u64::MAX - 1) as f64));
+        let (value, error) = frac(u64::MAX - 1, u64::MAX).value_with_rounding_error();
+        assert_eq!((value, error), (1.0, 1.0 / u64::MAX as f64));
+        // 1/(2^64 - 1) rounds to 2^-64, about 2^-128 below it
+        let (value, error) = frac(1, u64::MAX).value_with_rounding_error();
+        assert_eq!((value, error), (2f64.powi(-64), 2f64.powi(-128)));
+    }
+
+    #[test]
+    fn exact_values_of_f64() {
+        let tenth = Fraction::from_f64_exact(0.1).unwrap();
+        assert_eq!((tenth.numerator(), tenth.denominator()), (3602879701896397, 1 << 55));
+        assert_eq!(Fraction::from_f64_exact(-0.0), Fraction128::new(0, 1));
+        assert_eq!(Fraction::from_f64_exact(6.0), Fraction128::new(6, 1));
+        assert_eq!(Fraction::from_f64_exact(-1.0), Err(FractionError::InvalidInput));
+        assert_eq!(Fraction::from_f64_exact(f64::NAN), Err(FractionError::InvalidInput));
+    }
+
+    #[test]
+    fn exact_values_need_a_denominator_up_to_two_to_the_127() {
+        assert_eq!(Fraction::from_f64_exact(2f64.powi(-127)), Fraction128::new(1, 1 << 127));
+        assert_eq!(Fraction::from_f64_exact(3.0 * 2f64.powi(-127)), Fraction128::new(3, 1 << 127));
+        assert_eq!(Fraction::from_f64_exact(2f64.powi(-128)), Err(FractionError::Overflow));
+        // Above 2^-127, but still not a multiple of it
+        assert_eq!(Fraction::from_f64_exact(3.0 * 2f64.powi(-128)), Err(FractionError::Overflow));
+        assert_eq!(Fraction::from_f64_exact(5e-324), Err(FractionError::Overflow));
+        let largest = Fraction::from_f64_exact(2f64.powi(128) - 2f64.powi(75)).unwrap();
+        assert_eq!(largest.numerator(), u128::MAX - ((1 << 75) - 1));
+        assert_eq!(Fraction::from_f64_exact(2f64.powi(128)), Err(FractionError::Overflow));
+    }
+}
@@ -0,0 +1,171 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::fraction::{decompose, write_fraction_art};
+use crate::number_theory::gcd_u128;
+use crate::{Fraction, FractionError, Mediant};
+
+/// A [`Fraction`] with `u128` components, for values whose numerator or
+/// denominator does not fit in `u64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fraction128 {
+    numerator: u128,
+    denominator: u128,
+}
+
+impl Fraction128 {
+    pub fn new(numerator: u128, denominator: u128) -> Result<Self, FractionError> {
+        if denominator == 0 {
+            Err(FractionError::DivByZero)
+        } else {
+            Ok(Self { numerator, denominator })
+        }
+    }
+
+    pub fn numerator(&self) -> u128 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> u128 {
+        self.denominator
+    }
+
+    /// Returns the decimal value of this fraction.
+    pub fn value(&self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    /// Compares this fraction with `real` exactly; see
+    /// [`Fraction::cmp_with_real`].
+    ///
+    /// The cross products can need up to 181 bits, so they are taken as
+    /// (high, low) pairs of `u128` words.
+    pub fn cmp_with_real(&self, real: f64) -> Ordering {
+        if real.is_nan() || real == f64::INFINITY {
+            return Ordering::Less;
+        }
+        if real < 0.0 || real == f64::NEG_INFINITY {
+            return Ordering::Greater;
+        }
+        let (mantissa, exponent) = decompose(real);
+        let (p, q) = (self.numerator, self.denominator);
+        if mantissa == 0 || p == 0 {
+            return p.cmp(&(mantissa as u128));
+        }
+        // Compare p/q with m * 2^e as p * 2^-e against q * m
+        let qm = widening_mul(q, mantissa);
+        if exponent >= 0 {
+            // q * m * 2^e has at least 128 bits, more than p can have
+            if qm.0 != 0 || qm.1.ilog2() + exponent as u32 >= 128 {
+                return Ordering::Less;
+            }
+            p.cmp(&(qm.1 << exponent))
+        } else {
+            let shift = exponent.unsigned_abs();
+            // p * 2^-e has at least 256 bits, more than q * m < 2^181 can have
+            if p.ilog2() + shift >= 255 {
+                return Ordering::Greater;
+            }
+            let shifted = if shift >= 128 {
+                (p << (shift - 128), 0)
+            } else {
+                (p >> (128 - shift), p << shift)
+            };
+            shifted.cmp(&qm)
+        }
+    }
+
+    /// Returns the equivalent fraction in lowest terms.
+    pub fn reduce(&self) -> Self {
+        let divisor = gcd_u128(self.numerator, self.denominator);
+        Self {
+            numerator: self.numerator / divisor,
+            denominator: self.denominator / divisor,
+        }
+    }
+
+    /// Computes the mediant (a+c)/(b+d); see [`Fraction::mediant`].
+    ///
+    /// Returns `Overflow` if either sum exceeds `u128::MAX`.
+    pub fn mediant(&self, other: &Fraction128) -> Result<Self, FractionError> {
+        let numerator = self.numerator.checked_add(other.numerator);
+        let denominator = self.denominator.checked_add(other.denominator);
+        match numerator.zip(denominator) {
+            Some((numerator, denominator)) => Self::new(numerator, denominator),
+            None => Err(FractionError::Overflow),
+        }
+    }
+}
+
+/// Returns a * b as (high, low) `u128` words.
+fn widening_mul(a: u128, b: u64) -> (u128, u128) {
+    let b = b as u128;
+    let low = (a as u64 as u128) * b;
+    let high = (a >> 64) * b;
+    let (sum, carry) = low.overflowing_add(high << 64);
+    ((high >> 64) + carry as u128, sum)
+}
+
+impl Mediant for Fraction128 {
+    fn mediant(&self, other: &Self) -> Result<Self, FractionError> {
+        Fraction128::mediant(self, other)
+    }
+}
+
+impl From<Fraction> for Fraction128 {
+    fn from(frac: Fraction) -> Self {
+        Self {
+            numerator: frac.numerator() as u128,
+            denominator: frac.denominator() as u128,
+        }
+    }
+}
+
+impl TryFrom<Fraction128> for Fraction {
+    type Error = FractionError;
+
+    /// Narrows to `u64` components, failing with `Overflow` if either is too
+    /// large. The fraction is not reduced first.
+    fn try_from(frac: Fraction128) -> Result<Self, FractionError> {
+        let numerator = u64::try_from(frac.numerator).ok();
+        let denominator = u64::try_from(frac.denominator).ok();
+        Fraction::from_checked(numerator, denominator)
+    }
+}
+
+/// Formats with the same layout as [`Fraction`].
+impl fmt::Display for Fraction128 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_fraction_art(f, self.value(), self.numerator, self.denominator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cmp_with_real_matches_fraction() {
+        for (numerator, denominator) in [(0, 1), (1, 3), (355, 113), (u64::MAX, 1), (1, u64::MAX)] {
+            let frac = Fraction::new(numerator, denominator).unwrap();
+            for real in [0.0, 1e-300, 0.1, 1.0 / 3.0, std::f64::consts::PI, 1e19, 1e300, f64::INFINITY, -1.0] {
+                assert_eq!(Fraction128::from(frac).cmp_with_real(real), frac.cmp_with_real(real));
+            }
+        }
+    }
+
+    #[test]
+    fn cmp_with_real_beyond_u64() {
+        let max = Fraction128::new(u128::MAX, 1).unwrap();
+        assert_eq!(max.cmp_with_real(3.4e38), Ordering::Greater);
+        assert_eq!(max.cmp_with_real(3.5e38), Ordering::Less);
+        let tiny = Fraction128::new(1, u128::MAX).unwrap();
+        assert_eq!(tiny.cmp_with_real(1e-300), Ordering::Greater);
+        assert_eq!(tiny.cmp_with_real(1e-38), Ordering::Less);
+        // (2^100 + 1) / 2^100 lies strictly between 1 and the next f64
+        let above_one = Fraction128::new((1 << 100) + 1, 1 << 100).unwrap();
+        assert_eq!(above_one.cmp_with_real(1.0), Ordering::Greater);
+        assert_eq!(above_one.cmp_with_real(1.0 + f64::EPSILON), Ordering::Less);
+        assert_eq!(Fraction128::new(1 << 100, 1 << 100).unwrap().cmp_with_real(1.0), Ordering::Equal);
+    }
+}
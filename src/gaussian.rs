@@ -0,0 +1,99 @@
+//! Fractions with a real and an imaginary part.
+
+use std::fmt;
+
+use crate::{farey, Fraction, FractionError};
+
+/// A complex number re + im·i whose parts are fractions.
+///
+/// Both parts are non-negative, like [`Fraction`] itself, so these cover the
+/// first quadrant of the rational lattice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GaussianFraction {
+    real: Fraction,
+    imag: Fraction,
+}
+
+impl GaussianFraction {
+    pub fn new(real: Fraction, imag: Fraction) -> Self {
+        Self { real, imag }
+    }
+
+    pub fn real(&self) -> Fraction {
+        self.real
+    }
+
+    pub fn imag(&self) -> Fraction {
+        self.imag
+    }
+
+    /// Adds part by part, reducing each result.
+    ///
+    /// Returns `Overflow` if either sum does not fit in `u64`.
+    pub fn add(&self, other: &GaussianFraction) -> Result<GaussianFraction, FractionError> {
+        Ok(Self {
+            real: (self.real + other.real)?.reduce(),
+            imag: (self.imag + other.imag)?.reduce(),
+        })
+    }
+
+    /// Returns |z|^2 = re^2 + im^2, reduced.
+    ///
+    /// The magnitude itself is usually irrational, but its square is exact.
+    /// Returns `Overflow` if an intermediate does not fit in `u64`.
+    pub fn magnitude_sq(&self) -> Result<Fraction, FractionError> {
+        let real_sq = (self.real * self.real)?.reduce();
+        let imag_sq = (self.imag * self.imag)?.reduce();
+        Ok((real_sq + imag_sq)?.reduce())
+    }
+}
+
+/// Approximates re + im·i by running [`farey`] on each part independently,
+/// so negative parts are rejected.
+pub fn farey_complex(re: f64, im: f64) -> Result<GaussianFraction, FractionError> {
+    Ok(GaussianFraction::new(farey(re)?, farey(im)?))
+}
+
+/// Formats as `3/4 + 1/7i`.
+impl fmt::Display for GaussianFraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} + {}/{}i",
+            self.real.numerator(),
+            self.real.denominator(),
+            self.imag.numerator(),
+            self.imag.denominator()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frac(numerator: u64, denominator: u64) -> Fraction {
+        Fraction::new(numerator, denominator).unwrap()
+    }
+
+    #[test]
+    fn arithmetic() {
+        let z = GaussianFraction::new(frac(1, 2), frac(1, 3));
+        let w = GaussianFraction::new(frac(1, 4), frac(2, 3));
+        let sum = z.add(&w).unwrap();
+        assert_eq!((sum.real(), sum.imag()), (frac(3, 4), frac(1, 1)));
+        // (3/5)^2 + (4/5)^2 = 1
+        assert_eq!(GaussianFraction::new(frac(3, 5), frac(4, 5)).magnitude_sq(), Ok(frac(1, 1)));
+        assert_eq!(z.magnitude_sq(), Ok(frac(13, 36)));
+        let huge = GaussianFraction::new(frac(u64::MAX, 1), frac(0, 1));
+        assert_eq!(huge.add(&huge), Err(FractionError::Overflow));
+    }
+
+    #[test]
+    fn farey_complex_approximates_each_part() {
+        let z = farey_complex(0.75, 0.5).unwrap();
+        assert_eq!((z.real().reduce(), z.imag().reduce()), (frac(3, 4), frac(1, 2)));
+        assert_eq!(z.to_string(), "3/4 + 1/2i");
+        assert_eq!(farey_complex(0.5, -1.0), Err(FractionError::InvalidInput));
+    }
+}
@@ -0,0 +1,77 @@
+//! # Farey Approximation
+//!
+//! Approximates real numbers as fractions using the Farey sequence properties.
+//!
+//! The Farey sequence F_n is the sequence of completely reduced fractions between
+//! 0 and 1, with denominators ≤ n, arranged in increasing order. A key property
+//! is that for any two adjacent fractions a/b and c/d in a Farey sequence, their
+//! mediant (a+c)/(b+d) lies between them.
+//!
+//! This crate uses the mediant property to perform a binary search, narrowing
+//! bounds until finding the closest rational approximation to any real number.
+//!
+//! Every function that approximates an `f64` checks it with [`validate_input`]
+//! first: NaN, infinities and negative numbers are rejected with
+//! `InvalidInput`, numbers of 2^64 or more with `Overflow`, and `-0.0` and
+//! subnormals are treated as 0.
+
+mod analysis;
+mod arithmetic;
+mod checked;
+mod continued_fraction;
+mod diatomic;
+mod eval;
+mod farey;
+mod farey_sequence;
+#[cfg(feature = "c-ffi")]
+pub mod ffi;
+mod fraction;
+mod fraction128;
+mod gaussian;
+mod mediant;
+mod number_theory;
+mod parse;
+mod periodic;
+mod reduced;
+mod safe;
+mod stern_brocot;
+mod validate;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use analysis::{
+    bisection_count, compare_algorithms, continued_fraction_depth, convergence_quality,
+    convergent_errors, farey_all_closer, farey_approximation, farey_certified, farey_error_bound,
+    farey_is_convergent, farey_verify, lagrange_constant_approx, ApproximationResult,
+    ComparisonResult, ConvergenceStats, VerificationResult,
+};
+pub use checked::CheckedFraction;
+pub use continued_fraction::{ContinuedFraction, ContinuedFractionDisplay, ContinuedFractionFormat};
+pub use diatomic::{stern_diatomic, stern_diatomic_fraction, stern_diatomic_index};
+pub use eval::eval_rational;
+pub use farey::{
+    farey, farey_bounded, farey_convergents, farey_convergents_with_steps, farey_step_n,
+    farey_with_steps,
+};
+pub use farey_sequence::{
+    are_consecutive_farey_neighbors, ceil_to_farey, farey_adjacency_list, farey_adjacent,
+    farey_filtered, farey_graph_degree, farey_kth, farey_neighbors, farey_next, farey_prev,
+    farey_rank, farey_sequence_len, farey_sequence_to_vec, farey_window, floor_to_farey,
+    FareySequence,
+};
+pub use fraction::{common_denominator, Fraction, FractionError, ParseFractionError};
+pub use fraction128::Fraction128;
+pub use gaussian::{farey_complex, GaussianFraction};
+pub use mediant::Mediant;
+pub use number_theory::{gcd, gcd_extended, lcm};
+pub use parse::fraction_from_decimal;
+pub use periodic::{continued_fraction_sqrt, sqrt_convergent, PeriodicContinuedFraction};
+pub use reduced::ReducedFraction;
+pub use safe::{farey_safe, SafeFraction};
+pub use stern_brocot::{
+    all_positive_rationals, display_stern_brocot_path, farey_path, mediant_chain,
+    mediant_path_decode, mediant_path_to_target, stern_brocot_ancestors, stern_brocot_children,
+    stern_brocot_decode, stern_brocot_encode, stern_brocot_path, Direction, MediantChain, MAX_DRAWN_TURNS,
+    MAX_PATH_TURNS,
+};
+pub use validate::validate_input;
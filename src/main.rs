@@ -1,150 +1,184 @@
-//! # Farey Approximation
+//! Command-line front end for the Farey approximation.
 //!
-//! Approximates real numbers as fractions using the Farey sequence properties.
-//!
-//! The Farey sequence F_n is the sequence of completely reduced fractions between
-//! 0 and 1, with denominators ≤ n, arranged in increasing order. A key property
-//! is that for any two adjacent fractions a/b and c/d in a Farey sequence, their
-//! mediant (a+c)/(b+d) lies between them.
-//!
-//! This tool uses the mediant property to perform a binary search, narrowing
-//! bounds until finding the closest rational approximation to any real number.
+//! Prints each bisection step as `frac(left) <- mediant -> frac(right)`, then
+//! the final approximation as ASCII art.
+
+use std::str::FromStr;
 
 use bpaf::Bpaf;
-use std::fmt;
+use mediant_rs::{
+    bisection_count, eval_rational, farey_verify, farey_with_steps, mediant_path_decode,
+    mediant_path_to_target, validate_input, ContinuedFraction, Fraction,
+};
 
 #[derive(Clone, Debug, Bpaf)]
 #[bpaf(options, version)]
 struct Args {
-    /// The real number
-    number: f64,
+    /// Print the Stern-Brocot path of the search as L/R turns
+    path: bool,
+    /// How to print the result: "art" (the default) or "html", which prints
+    /// only the result, as HTML and MathML
+    #[bpaf(argument("FORMAT"), fallback(Output::Art))]
+    output: Output,
+    /// Center the fraction bar of the result under the decimal point
+    centered: bool,
+    /// Instead of approximating the number, report how well the fraction N/D
+    /// approximates it
+    #[bpaf(argument("N/D"))]
+    verify: Option<String>,
+    /// Print only the number of bisections needed to come within --epsilon
+    count_bisections: bool,
+    /// The tolerance for --count-bisections
+    #[bpaf(argument("VALUE"), fallback(f64::EPSILON))]
+    epsilon: f64,
+    #[bpaf(external(input))]
+    input: Input,
 }
 
-/// A fraction represented as numerator/denominator.
-///
-/// Fractions are the building blocks of Farey sequences. In the context of this
-/// algorithm, we maintain two fractions (left and right bounds) and repeatedly
-/// compute their mediant to converge on a target value.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Fraction {
-    numerator: u64,
-    denominator: u64,
+#[derive(Clone, Copy, Debug)]
+enum Output {
+    Art,
+    Html,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct DivByZeroError;
+impl FromStr for Output {
+    type Err = String;
 
-impl fmt::Display for DivByZeroError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "division by zero: denominator cannot be zero")
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "art" => Ok(Self::Art),
+            "html" => Ok(Self::Html),
+            _ => Err(format!("unknown output format {s:?}, expected \"art\" or \"html\"")),
+        }
     }
 }
 
-impl std::error::Error for DivByZeroError {}
+#[derive(Clone, Debug, Bpaf)]
+enum Input {
+    Number {
+        /// The real number
+        number: f64,
+    },
+    NumberStr {
+        /// The real number as a decimal string, expanded exactly without
+        /// rounding to f64 (up to 38 significant digits)
+        number_str: String,
+    },
+    Eval {
+        /// Evaluate a fraction expression instead, e.g. "1/3 + (1/2)^2" or
+        /// "mediant(1/3, 1/4)"
+        #[bpaf(argument("EXPR"))]
+        eval: String,
+    },
+    Decode {
+        /// Print the fraction at the end of a Stern-Brocot path of L/R turns
+        /// instead, e.g. "RRRLLLLLL" for 22/7
+        #[bpaf(argument("PATH"))]
+        decode: String,
+    },
+}
 
-impl Fraction {
-    fn new(numerator: u64, denominator: u64) -> Result<Self, DivByZeroError> {
-        if denominator == 0 {
-            Err(DivByZeroError)
-        } else {
-            Ok(Self { numerator, denominator })
+fn main() {
+    let opts = args().run();
+    let number = match opts.input {
+        Input::Number { number } => number,
+        Input::NumberStr { number_str } => return print_decimal_expansion(&number_str),
+        Input::Eval { eval } => {
+            match eval_rational(&eval) {
+                Ok(value) => print_fraction(&value, opts.output, opts.centered),
+                Err(e) => eprintln!("Error: {e}"),
+            }
+            return;
         }
-    }
+        Input::Decode { decode } => {
+            match mediant_path_decode(&decode) {
+                Ok(frac) => print_fraction(&frac, opts.output, opts.centered),
+                Err(e) => eprintln!("Error: {e}"),
+            }
+            return;
+        }
+    };
+    let number = match validate_input(number) {
+        Ok(number) => number,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return;
+        }
+    };
 
-    /// Returns the decimal value of this fraction.
-    fn value(&self) -> f64 {
-        self.numerator as f64 / self.denominator as f64
+    if let Some(frac) = opts.verify {
+        return print_verification(&frac, number);
     }
 
-    /// Computes the mediant of two fractions.
-    ///
-    /// The mediant of a/b and c/d is (a+c)/(b+d). This is NOT the arithmetic mean,
-    /// but rather "Farey addition". The mediant has a key property: if a/b < c/d,
-    /// then a/b < mediant < c/d. This property enables binary search over rationals.
-    ///
-    /// Example: mediant of 1/2 and 1/3 is (1+1)/(2+3) = 2/5
-    fn mediant(&self, other: &Fraction) -> Result<Self, DivByZeroError> {
-        Self::new(
-            self.numerator + other.numerator,
-            self.denominator + other.denominator,
-        )
+    if opts.count_bisections {
+        match bisection_count(number, opts.epsilon) {
+            Ok(count) => println!("{count}"),
+            Err(e) => eprintln!("Error: {e}"),
+        }
+        return;
     }
-}
-
-/// Format the fraction as follows:
-///               27450985
-/// 0.33333339 ≈ ----------
-///               82352941
-impl fmt::Display for Fraction {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let value_str = format!("{:.15}", self.value())
-            .trim_end_matches('0')
-            .trim_end_matches('.')
-            .to_string();
 
-        let frac_width = self.numerator.max(self.denominator).to_string().len();
-        let sep_width = frac_width + 2;
-        // Right-align fractions to end at the same column as the separator
-        let pad = value_str.len() + 3 + sep_width;
-
-        write!(
-            f,
-            "\n{:>pad$}\n{value_str} ≈ {:-<sep_width$}\n{:>pad$}\n\n$ {value_str} ≈ frac({},{}) $",
-            self.numerator, "", self.denominator, self.numerator, self.denominator
-        )
+    if opts.path {
+        match mediant_path_to_target(number) {
+            Ok(path) => println!("{path}"),
+            Err(e) => eprintln!("Error: {e}"),
+        }
+        return;
     }
-}
-
-/// Approximates a real number as a fraction using the Farey/mediant algorithm.
-///
-/// # Algorithm
-///
-/// 1. Start with two bounds: left = floor(x)/1 and right = ceil(x)/1
-/// 2. Compute the mediant of left and right
-/// 3. If mediant equals target (within epsilon), we're done
-/// 4. If mediant > target, it becomes the new right bound (search left half)
-/// 5. If mediant < target, it becomes the new left bound (search right half)
-/// 6. Repeat until convergence
-///
-/// This is essentially binary search over the Stern-Brocot tree, which contains
-/// all positive rationals exactly once. The mediant operation naturally traverses
-/// this tree, guaranteeing we find the best rational approximation.
-/// see: https://cp-algorithms.com/others/stern_brocot_tree_farey_sequences.html
-fn farey(real_number: f64) -> Result<Fraction, DivByZeroError> {
-    // Initialize bounds: the target lies between floor(x) and ceil(x)
-    let mut left = Fraction::new(real_number.floor() as u64, 1)?;
-    let mut right = Fraction::new(real_number.ceil() as u64, 1)?;
-
-    loop {
-        // The mediant always lies strictly between left and right (when they differ)
-        let mediant = left.mediant(&right)?;
-        let mediant_value = mediant.value();
 
+    let result = farey_with_steps(number, |left, mediant, right| {
+        if let Output::Html = opts.output {
+            return;
+        }
         println!(
             "$ frac({},{}) <- {} -> frac({},{}) $",
-            left.numerator, left.denominator,
-            mediant_value,
-            right.numerator, right.denominator
+            left.numerator(), left.denominator(),
+            mediant.value(),
+            right.numerator(), right.denominator()
         );
+    });
+    match result {
+        Ok(approx) => print_fraction(&approx, opts.output, opts.centered),
+        Err(e) => eprintln!("Error: {e}"),
+    }
+}
 
-        // Convergence: mediant is close enough to target
-        if (real_number - mediant_value).abs() < f64::EPSILON {
-            return Ok(mediant);
-        }
+fn print_fraction(frac: &Fraction, output: Output, centered: bool) {
+    match output {
+        Output::Art if centered => println!("{frac:#}"),
+        Output::Art => println!("{frac}"),
+        Output::Html => println!("{}\n{}", frac.to_html(), frac.to_html_math()),
+    }
+}
 
-        // Binary search: narrow the bounds based on which side the target falls
-        if mediant_value > real_number {
-            right = mediant;
-        } else {
-            left = mediant;
+/// Prints the [`farey_verify`] report for the fraction written as `frac`.
+fn print_verification(frac: &str, number: f64) {
+    let report = eval_rational(frac).and_then(|frac| farey_verify(frac, number));
+    match report {
+        Ok(report) => {
+            let yes_no = |b: bool| if b { "yes" } else { "no" };
+            println!("convergent: {}", yes_no(report.is_convergent));
+            println!("best for its denominator: {}", yes_no(report.is_best_for_denom));
+            println!("error: {:e}", report.error);
+            println!("Stern-Brocot depth: {}", report.stern_brocot_depth);
+            println!("continued fraction: {}", report.continued_fraction);
         }
+        Err(e) => eprintln!("Error: {e}"),
     }
 }
 
-fn main() {
-    let opts = args().run();
-    match farey(opts.number) {
-        Ok(approx) => println!("{approx}"),
-        Err(e) => eprintln!("Error: {e}"),
+/// Prints the exact continued fraction of a decimal string and its deepest
+/// convergent that fits in `u64`.
+fn print_decimal_expansion(number_str: &str) {
+    let cf = match ContinuedFraction::from_decimal_str(number_str) {
+        Ok(cf) => cf,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return;
+        }
+    };
+    println!("{cf}");
+    if let Some(best) = cf.convergents().last() {
+        println!("{best}");
     }
 }
@@ -0,0 +1,33 @@
+use crate::{Fraction, FractionError};
+
+/// Types with a mediant ("Farey addition") operation.
+///
+/// The mediant of a/b and c/d is (a+c)/(b+d). Any type implementing this can
+/// drive a Stern-Brocot search, e.g.
+///
+/// ```text
+/// fn stern_brocot_search<T: Mediant + PartialOrd>(lo: T, hi: T, target: f64) -> ...
+/// ```
+///
+/// so wider representations only need to supply the operation itself.
+///
+/// # Algebraic structure
+///
+/// `Fraction` under mediant is a magma: a closed binary operation and nothing
+/// more. On the raw (numerator, denominator) pairs it is componentwise
+/// addition, but it does not respect equality of values (1/2 ⊕ 1/3 = 2/5
+/// while 2/4 ⊕ 1/3 = 3/7), so it is not an operation on the rationals. The
+/// Stern-Brocot tree is generated from 0/1 and 1/0 by repeatedly taking
+/// mediants of neighbours, and each node arises from exactly one such
+/// expression; this is the sense in which the tree is the free magma on two
+/// generators.
+pub trait Mediant: Sized {
+    /// Returns the mediant of `self` and `other`.
+    fn mediant(&self, other: &Self) -> Result<Self, FractionError>;
+}
+
+impl Mediant for Fraction {
+    fn mediant(&self, other: &Self) -> Result<Self, FractionError> {
+        Fraction::mediant(self, other)
+    }
+}
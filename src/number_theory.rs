@@ -0,0 +1,119 @@
+//! Integer utilities underlying the fraction algorithms.
+
+/// Greatest common divisor by the Euclidean algorithm. gcd(0, 0) = 0.
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// [`gcd`] for `u128`, used by [`Fraction128`](crate::Fraction128).
+pub(crate) fn gcd_u128(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Extended Euclidean algorithm: returns (s, t, g) with s*a + t*b = g = gcd(a, b).
+///
+/// The coefficients satisfy |s| <= b/g and |t| <= a/g, so they always fit in
+/// `i128` for `u64` inputs.
+pub(crate) fn bezout(a: u64, b: u64) -> (i128, i128, u64) {
+    let (mut old_r, mut r) = (a as i128, b as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    let (mut old_t, mut t) = (0i128, 1i128);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+        (old_t, t) = (t, old_t - quotient * t);
+    }
+    (old_s, old_t, old_r as u64)
+}
+
+/// Extended Euclidean algorithm: returns Bézout coefficients (s, t) and
+/// g = gcd(a, b) with s*a + t*b = g.
+///
+/// # Panics
+///
+/// If a coefficient does not fit in `i64`. Since |s| <= b/g and |t| <= a/g,
+/// this can only happen when an input exceeds `i64::MAX`.
+pub fn gcd_extended(a: u64, b: u64) -> (i64, i64, u64) {
+    let (s, t, g) = bezout(a, b);
+    let narrow = |c: i128| i64::try_from(c).expect("Bézout coefficient exceeds i64");
+    (narrow(s), narrow(t), g)
+}
+
+/// Inverse of `a` modulo `m`, in 0..m. Requires gcd(a, m) = 1 and m >= 1.
+pub(crate) fn mod_inverse(a: u64, m: u64) -> u64 {
+    let (s, _, g) = bezout(a, m);
+    debug_assert_eq!(g, 1, "{a} has no inverse modulo {m}");
+    s.rem_euclid(m as i128) as u64
+}
+
+/// Least common multiple, or `None` if it does not fit in `u64`. lcm(0, n) = 0.
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn zero_cases() {
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(gcd(0, 12), 12);
+        assert_eq!(gcd(12, 0), 12);
+        assert_eq!(lcm(0, 0), Some(0));
+        assert_eq!(lcm(0, u64::MAX), Some(0));
+        assert_eq!(gcd_extended(0, 0), (1, 0, 0));
+        assert_eq!(gcd_extended(0, 7), (0, 1, 7));
+        assert_eq!(gcd_extended(7, 0), (1, 0, 7));
+    }
+
+    #[test]
+    fn lcm_overflow() {
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(u64::MAX, u64::MAX), Some(u64::MAX));
+        assert_eq!(lcm(1 << 32, 1 << 32), Some(1 << 32));
+        assert_eq!(lcm(u64::MAX, 2), None);
+        assert_eq!(lcm(1 << 32, (1 << 32) + 1), None);
+    }
+
+    #[test]
+    fn bezout_examples() {
+        assert_eq!(gcd_extended(240, 46), (-9, 47, 2));
+        assert_eq!(mod_inverse(3, 7), 5);
+        let (s, t, g) = bezout(u64::MAX, u64::MAX - 1);
+        assert_eq!(g, 1);
+        assert_eq!(s * u64::MAX as i128 + t * (u64::MAX - 1) as i128, 1);
+    }
+
+    proptest! {
+        #[test]
+        fn bezout_identity(a: u64, b: u64) {
+            let (s, t, g) = bezout(a, b);
+            prop_assert_eq!(g, gcd(a, b));
+            prop_assert_eq!(s * a as i128 + t * b as i128, g as i128);
+            // |s| <= b/g and |t| <= a/g, or 1 when that quotient is 0
+            prop_assert!(s.unsigned_abs() * g as u128 <= b.max(g) as u128);
+            prop_assert!(t.unsigned_abs() * g as u128 <= a.max(g) as u128);
+        }
+
+        #[test]
+        fn gcd_extended_identity(a in 0..=i64::MAX as u64, b in 0..=i64::MAX as u64) {
+            let (s, t, g) = gcd_extended(a, b);
+            prop_assert_eq!(s as i128 * a as i128 + t as i128 * b as i128, g as i128);
+            prop_assert_eq!(a % g.max(1), 0);
+            prop_assert_eq!(b % g.max(1), 0);
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::number_theory::gcd_u128;
+use crate::{farey_bounded, Fraction, FractionError, ParseFractionError};
+
+/// A repeating tail must span at least this many digits to be taken as
+/// exact, so that "0.11" stays 11/100 while "0.111111" becomes 1/9.
+const MIN_REPEATING_DIGITS: usize = 6;
+
+/// Parses a decimal string such as `"0.142857142857142"` and returns the
+/// closest fraction with denominator at most `max_denominator`.
+///
+/// First looks for a repeating tail: if the digits after the point end in at
+/// least two full copies of some block, spanning at least six digits, the
+/// string is read as that repeating decimal, so the example above is exactly
+/// 1/7. A repeating decimal 0.AB̅ with preperiod A of a digits and period B of
+/// b digits is (AB - A) / (10^a (10^b - 1)). If that fraction fits under the
+/// bound it is the answer; otherwise the string is rounded to `f64`, which
+/// keeps about 17 significant digits, and handed to [`farey_bounded`].
+///
+/// Returns a [`ParseFractionError`] (as `FractionError::Parse`) for anything
+/// other than digits with at most one decimal point, and `InvalidInput` for
+/// a bound of zero.
+pub fn fraction_from_decimal(s: &str, max_denominator: u64) -> Result<Fraction, FractionError> {
+    let s = s.trim();
+    let s = s.strip_prefix('+').unwrap_or(s);
+    let (integer, fractional) = s.split_once('.').unwrap_or((s, ""));
+    if let Some(c) = integer.chars().chain(fractional.chars()).find(|c| !c.is_ascii_digit()) {
+        return Err(ParseFractionError::InvalidCharacter(c).into());
+    }
+    if integer.is_empty() && fractional.is_empty() {
+        return Err(ParseFractionError::Empty.into());
+    }
+    if max_denominator == 0 {
+        return Err(FractionError::InvalidInput);
+    }
+
+    if let Some(exact) = repeating_decimal(integer, fractional) {
+        if exact.denominator() <= max_denominator {
+            return Ok(exact);
+        }
+    }
+    let value: f64 = s.parse().map_err(|_| ParseFractionError::Empty)?;
+    farey_bounded(value, max_denominator)
+}
+
+/// Reads `integer.fractional` as a repeating decimal if its digits end in a
+/// long enough repeating block, trying the shortest preperiod and then the
+/// shortest period first. Returns the value reduced, or `None` if there is no
+/// such block or the fraction does not fit in `u64`.
+fn repeating_decimal(integer: &str, fractional: &str) -> Option<Fraction> {
+    let digits = fractional.as_bytes();
+    for start in 0..digits.len() {
+        let tail = &digits[start..];
+        if tail.len() < MIN_REPEATING_DIGITS {
+            return None;
+        }
+        let repeats_every = |len: usize| (len..tail.len()).all(|i| tail[i] == tail[i - len]);
+        let Some(period) = (1..=tail.len() / 2).find(|&len| repeats_every(len)) else {
+            continue;
+        };
+        // The digits through the preperiod, and through the first period, as
+        // whole numbers
+        let prefix = format!("0{integer}{}", &fractional[..start]);
+        let through_period = format!("{prefix}{}", &fractional[start..start + period]);
+        let numerator = through_period
+            .parse::<u128>()
+            .ok()?
+            .checked_sub(prefix.parse::<u128>().ok()?)?;
+        let denominator = 10u128
+            .checked_pow(start as u32)?
+            .checked_mul(10u128.checked_pow(period as u32)? - 1)?;
+        let divisor = gcd_u128(numerator, denominator);
+        let numerator = u64::try_from(numerator / divisor).ok()?;
+        let denominator = u64::try_from(denominator / divisor).ok()?;
+        return Fraction::new(numerator, denominator).ok();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frac(numerator: u64, denominator: u64) -> Fraction {
+        Fraction::new(numerator, denominator).unwrap()
+    }
+
+    #[test]
+    fn repeating_tails_are_exact() {
+        assert_eq!(fraction_from_decimal("0.142857142857142", 1000), Ok(frac(1, 7)));
+        assert_eq!(fraction_from_decimal("0.111111", 1000), Ok(frac(1, 9)));
+        assert_eq!(fraction_from_decimal(" +0.333333 ", 1000), Ok(frac(1, 3)));
+        // The block only has to repeat after a preperiod
+        assert_eq!(fraction_from_decimal("0.1666666", 1000), Ok(frac(1, 6)));
+        assert_eq!(fraction_from_decimal("0.58333333", 1000), Ok(frac(7, 12)));
+        assert_eq!(fraction_from_decimal("1.2345454545", 1000), Ok(frac(679, 550)));
+    }
+
+    #[test]
+    fn short_or_irregular_tails_are_approximated() {
+        assert_eq!(fraction_from_decimal("0.11", 1000), Ok(frac(11, 100)));
+        assert_eq!(fraction_from_decimal("3.14159265358979", 1000), Ok(frac(355, 113)));
+        assert_eq!(repeating_decimal("0", "11"), None);
+        assert_eq!(repeating_decimal("3", "14159265358979"), None);
+    }
+
+    #[test]
+    fn falls_back_when_the_bound_is_too_small() {
+        let sevenths = fraction_from_decimal("0.142857142857142", 6).unwrap();
+        assert_eq!(sevenths, farey_bounded(0.142857142857142, 6).unwrap());
+        assert!(sevenths.denominator() <= 6);
+        assert_eq!(fraction_from_decimal("0.1666666", 5), farey_bounded(0.1666666, 5));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(fraction_from_decimal("0.1x", 10), Err(ParseFractionError::InvalidCharacter('x').into()));
+        assert_eq!(fraction_from_decimal("-0.5", 10), Err(ParseFractionError::InvalidCharacter('-').into()));
+        assert_eq!(fraction_from_decimal("1.2.3", 10), Err(ParseFractionError::InvalidCharacter('.').into()));
+        assert_eq!(fraction_from_decimal("", 10), Err(ParseFractionError::Empty.into()));
+        assert_eq!(fraction_from_decimal(".", 10), Err(ParseFractionError::Empty.into()));
+        assert_eq!(fraction_from_decimal("0.5", 0), Err(FractionError::InvalidInput));
+        assert_eq!(fraction_from_decimal("0.142857142857142", 0), Err(FractionError::InvalidInput));
+    }
+}
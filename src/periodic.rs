@@ -0,0 +1,201 @@
+use std::fmt;
+
+use crate::continued_fraction::convergents_of;
+use crate::{Fraction, FractionError};
+
+/// An eventually periodic continued fraction [a0; a1, ..., (p1, ..., pk)].
+///
+/// The terms in `period` repeat forever. By Lagrange's theorem these are
+/// exactly the quadratic irrationals, so this represents numbers like √2 =
+/// [1; (2)] exactly, where a [`ContinuedFraction`](crate::ContinuedFraction)
+/// can only hold a truncation. An empty period gives a rational.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeriodicContinuedFraction {
+    initial: Vec<u64>,
+    period: Vec<u64>,
+}
+
+impl PeriodicContinuedFraction {
+    /// Builds [initial; (period)].
+    ///
+    /// Returns `InvalidInput` if `initial` is empty or any term after a0 is zero.
+    pub fn new(initial: Vec<u64>, period: Vec<u64>) -> Result<Self, FractionError> {
+        if initial.is_empty() || initial[1..].contains(&0) || period.contains(&0) {
+            return Err(FractionError::InvalidInput);
+        }
+        Ok(Self { initial, period })
+    }
+
+    /// Expands √n.
+    ///
+    /// With m_0 = 0, d_0 = 1 and a_0 = floor(√n), each step computes
+    ///
+    ///   m' = d a - m,   d' = (n - m'^2) / d,   a' = floor((a_0 + m') / d')
+    ///
+    /// which stays in integers because d always divides n - m'^2. The period
+    /// ends at the first a' = 2 a_0, and its terms before that are a
+    /// palindrome. The period length also governs the fundamental solution of
+    /// Pell's equation x^2 - n y^2 = ±1. Perfect squares have an empty period.
+    pub fn from_sqrt(n: u64) -> Self {
+        let root = n.isqrt();
+        let mut period = Vec::new();
+        if root * root != n {
+            let (n, root) = (n as u128, root as u128);
+            let (mut m, mut d, mut a) = (0u128, 1u128, root);
+            while a != 2 * root {
+                m = d * a - m;
+                d = (n - m * m) / d;
+                a = (root + m) / d;
+                period.push(a as u64);
+            }
+        }
+        Self { initial: vec![root], period }
+    }
+
+    /// Returns the terms before the repeating part.
+    pub fn initial(&self) -> &[u64] {
+        &self.initial
+    }
+
+    /// Returns the repeating terms.
+    pub fn period(&self) -> &[u64] {
+        &self.period
+    }
+
+    /// Returns the terms, cycling through the period forever.
+    pub fn terms(&self) -> impl Iterator<Item = u64> + '_ {
+        self.initial.iter().chain(self.period.iter().cycle()).copied()
+    }
+
+    /// Returns the convergents, cycling through the period indefinitely.
+    ///
+    /// Ends once a convergent would overflow `u64` (or after the initial terms
+    /// if the period is empty).
+    pub fn convergents_infinite(&self) -> impl Iterator<Item = Fraction> + '_ {
+        convergents_of(self.terms())
+    }
+
+    /// Returns the convergent that uses the first `depth + 1` terms.
+    ///
+    /// For a rational (empty period) deeper requests return its exact value.
+    /// Returns `Overflow` if that convergent does not fit in `u64`.
+    pub fn evaluate_at_depth(&self, depth: usize) -> Result<Fraction, FractionError> {
+        let available = if self.period.is_empty() { self.initial.len() } else { usize::MAX };
+        let depth = depth.min(available - 1);
+        self.convergents_infinite()
+            .nth(depth)
+            .ok_or(FractionError::Overflow)
+    }
+}
+
+/// Formats as `[1; 1, (2, 3)]`, with the period in parentheses.
+impl fmt::Display for PeriodicContinuedFraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |terms: &[u64]| terms.iter().map(u64::to_string).collect::<Vec<_>>().join(", ");
+        let (a0, rest) = self.initial.split_first().expect("initial terms are never empty");
+
+        let mut tail = Vec::new();
+        if !rest.is_empty() {
+            tail.push(join(rest));
+        }
+        if !self.period.is_empty() {
+            tail.push(format!("({})", join(&self.period)));
+        }
+        if tail.is_empty() {
+            write!(f, "[{a0}]")
+        } else {
+            write!(f, "[{a0}; {}]", tail.join(", "))
+        }
+    }
+}
+
+/// Returns the continued fraction of √n, e.g. √2 = [1; (2)], √3 = [1; (1, 2)]
+/// and √5 = [2; (4)].
+///
+/// The same as [`PeriodicContinuedFraction::from_sqrt`]. The state (m, d, a)
+/// of the expansion first repeats right after the term 2 a_0, so the period
+/// is complete there without keeping earlier states to compare against.
+pub fn continued_fraction_sqrt(n: u64) -> PeriodicContinuedFraction {
+    PeriodicContinuedFraction::from_sqrt(n)
+}
+
+/// Returns the convergent p_k/q_k of √n, counting from p_0/q_0 = floor(√n)/1.
+///
+/// With r the period length of √n, the convergents at k = r - 1, 2r - 1, ...
+/// solve Pell's equation p^2 - n q^2 = ±1; for √2 (r = 1) that is every one
+/// of them: 1/1, 3/2, 7/5, 17/12, ... Returns `Overflow` if the convergent
+/// does not fit in `u64`, and the exact root for perfect squares.
+pub fn sqrt_convergent(n: u64, k: usize) -> Result<Fraction, FractionError> {
+    continued_fraction_sqrt(n).evaluate_at_depth(k)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sqrt_periods() {
+        let sqrt2 = PeriodicContinuedFraction::from_sqrt(2);
+        assert_eq!(sqrt2.initial(), &[1]);
+        assert_eq!(sqrt2.period(), &[2]);
+        assert_eq!(sqrt2.to_string(), "[1; (2)]");
+        assert_eq!(PeriodicContinuedFraction::from_sqrt(3).period(), &[1, 2]);
+        assert_eq!(PeriodicContinuedFraction::from_sqrt(7).period(), &[1, 1, 1, 4]);
+        // Perfect squares are rational
+        let sqrt16 = PeriodicContinuedFraction::from_sqrt(16);
+        assert!(sqrt16.period().is_empty());
+        assert_eq!(sqrt16.to_string(), "[4]");
+    }
+
+    #[test]
+    fn convergents_cycle_through_the_period() {
+        let sqrt2 = PeriodicContinuedFraction::from_sqrt(2);
+        let first: Vec<(u64, u64)> = sqrt2.convergents_infinite().take(5).map(Fraction::into_parts).collect();
+        assert_eq!(first, [(1, 1), (3, 2), (7, 5), (17, 12), (41, 29)]);
+        assert_eq!(sqrt2.evaluate_at_depth(3), Fraction::new(17, 12));
+        assert_eq!(sqrt2.evaluate_at_depth(200), Err(FractionError::Overflow));
+        let cf = PeriodicContinuedFraction::new(vec![1, 1], vec![2, 3]).unwrap();
+        let terms: Vec<u64> = cf.terms().take(6).collect();
+        assert_eq!(terms, [1, 1, 2, 3, 2, 3]);
+        assert_eq!(cf.to_string(), "[1; 1, (2, 3)]");
+    }
+
+    #[test]
+    fn rational_depth_is_capped() {
+        let cf = PeriodicContinuedFraction::new(vec![3, 7], Vec::new()).unwrap();
+        assert_eq!(cf.evaluate_at_depth(10), Fraction::new(22, 7));
+        assert_eq!(PeriodicContinuedFraction::new(Vec::new(), vec![1]), Err(FractionError::InvalidInput));
+        assert_eq!(PeriodicContinuedFraction::new(vec![1, 0], Vec::new()), Err(FractionError::InvalidInput));
+    }
+
+    #[test]
+    fn sqrt_expansions() {
+        assert_eq!(continued_fraction_sqrt(2).to_string(), "[1; (2)]");
+        assert_eq!(continued_fraction_sqrt(3).to_string(), "[1; (1, 2)]");
+        assert_eq!(continued_fraction_sqrt(5).to_string(), "[2; (4)]");
+        assert_eq!(continued_fraction_sqrt(5), PeriodicContinuedFraction::from_sqrt(5));
+        // The period always ends in 2 a0
+        for n in (2..200).filter(|&n| (n as f64).sqrt().fract() != 0.0) {
+            let cf = continued_fraction_sqrt(n);
+            assert_eq!(cf.period().last(), Some(&(2 * cf.initial()[0])), "√{n}");
+        }
+    }
+
+    #[test]
+    fn sqrt_convergents_solve_pell() {
+        // For √2 every convergent solves x² - 2y² = ±1, alternating from -1
+        for k in 0..30 {
+            let (x, y) = sqrt_convergent(2, k).unwrap().into_parts();
+            let pell = (x as i128).pow(2) - 2 * (y as i128).pow(2);
+            assert_eq!(pell, if k % 2 == 0 { -1 } else { 1 }, "k = {k}");
+        }
+        // For √3 (period 2) the odd ones solve x² - 3y² = 1
+        for k in [1, 3, 5, 7] {
+            let (x, y) = sqrt_convergent(3, k).unwrap().into_parts();
+            assert_eq!((x as i128).pow(2) - 3 * (y as i128).pow(2), 1);
+        }
+        assert_eq!(sqrt_convergent(5, 1), Fraction::new(9, 4));
+        assert_eq!(sqrt_convergent(9, 5), Fraction::new(3, 1));
+        assert_eq!(sqrt_convergent(2, 200), Err(FractionError::Overflow));
+    }
+}
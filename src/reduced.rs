@@ -0,0 +1,110 @@
+use std::cmp::Ordering;
+use std::ops::{Add, Deref, Div, Mul, Sub};
+
+use crate::{Fraction, FractionError};
+
+/// A [`Fraction`] that is always in lowest terms.
+///
+/// Because every value has exactly one reduced form, the derived `PartialEq`
+/// and `Hash` compare values rather than representations, so 2/4 and 1/2 are
+/// the same `HashMap` key. Arithmetic reduces its results, and `Deref` gives
+/// access to every `Fraction` method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReducedFraction(Fraction);
+
+impl ReducedFraction {
+    /// Builds the reduced form of `numerator/denominator`.
+    pub fn new(numerator: u64, denominator: u64) -> Result<Self, FractionError> {
+        Fraction::new_reduced(numerator, denominator).map(Self)
+    }
+
+    /// Returns the underlying fraction.
+    pub fn into_inner(self) -> Fraction {
+        self.0
+    }
+}
+
+impl From<Fraction> for ReducedFraction {
+    fn from(frac: Fraction) -> Self {
+        Self(frac.reduce())
+    }
+}
+
+impl Deref for ReducedFraction {
+    type Target = Fraction;
+
+    fn deref(&self) -> &Fraction {
+        &self.0
+    }
+}
+
+impl Ord for ReducedFraction {
+    fn cmp(&self, other: &Self) -> Ordering {
+        Fraction::cross_product(self.0, other.0).cmp(&0)
+    }
+}
+
+impl PartialOrd for ReducedFraction {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+macro_rules! reduced_op {
+    ($trait:ident, $method:ident) => {
+        impl $trait for ReducedFraction {
+            type Output = Result<ReducedFraction, FractionError>;
+
+            fn $method(self, rhs: ReducedFraction) -> Self::Output {
+                self.0.$method(rhs.0).map(ReducedFraction::from)
+            }
+        }
+    };
+}
+
+reduced_op!(Add, add);
+reduced_op!(Sub, sub);
+reduced_op!(Mul, mul);
+reduced_op!(Div, div);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn reduced(numerator: u64, denominator: u64) -> ReducedFraction {
+        ReducedFraction::new(numerator, denominator).unwrap()
+    }
+
+    #[test]
+    fn arithmetic_comes_out_reduced() {
+        let half = (reduced(1, 6) + reduced(1, 3)).unwrap();
+        assert_eq!(half.into_parts(), (1, 2));
+        assert_eq!((reduced(3, 4) - reduced(1, 4)).unwrap().into_parts(), (1, 2));
+        assert_eq!((reduced(2, 3) * reduced(3, 4)).unwrap().into_parts(), (1, 2));
+        assert_eq!((reduced(1, 2) / reduced(1, 4)).unwrap().into_parts(), (2, 1));
+        assert_eq!((reduced(1, 2) - reduced(1, 2)).unwrap().into_parts(), (0, 1));
+        assert_eq!(reduced(1, 2) / reduced(0, 1), Err(FractionError::DivByZero));
+    }
+
+    #[test]
+    fn equal_values_are_equal_keys() {
+        assert_eq!(reduced(2, 4), reduced(1, 2));
+        assert_eq!(ReducedFraction::from(Fraction::new(6, 9).unwrap()), reduced(2, 3));
+        let mut counts: HashMap<ReducedFraction, u32> = HashMap::new();
+        for (p, q) in [(1, 2), (2, 4), (3, 6), (1, 3), (5, 15), (50, 100)] {
+            *counts.entry(reduced(p, q)).or_default() += 1;
+        }
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[&reduced(1, 2)], 4);
+        assert_eq!(counts[&reduced(1, 3)], 2);
+    }
+
+    #[test]
+    fn ordering_is_by_value() {
+        assert!(reduced(1, 3) < reduced(1, 2));
+        assert!(reduced(2, 3) > reduced(3, 5));
+        assert_eq!(reduced(4, 8).cmp(&reduced(1, 2)), Ordering::Equal);
+    }
+}
@@ -0,0 +1,139 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::farey::{Bisection, SearchBound};
+use crate::{Fraction, Fraction128, FractionError, Mediant};
+
+/// A fraction that widens from `u64` to `u128` components when it has to.
+///
+/// Starts out `Small`; [`SafeFraction::mediant`] returns `Large` as soon as
+/// the `u64` sums would overflow, so a Stern-Brocot search can keep going past
+/// the point where [`Fraction`] gives up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SafeFraction {
+    Small(Fraction),
+    Large(Fraction128),
+}
+
+impl SafeFraction {
+    /// Returns the decimal value of this fraction.
+    pub fn value(&self) -> f64 {
+        match self {
+            Self::Small(frac) => frac.value(),
+            Self::Large(frac) => frac.value(),
+        }
+    }
+
+    /// Returns this fraction with `u128` components, whichever variant it is.
+    pub fn to_fraction128(&self) -> Fraction128 {
+        match *self {
+            Self::Small(frac) => frac.into(),
+            Self::Large(frac) => frac,
+        }
+    }
+
+    /// Computes the mediant (a+c)/(b+d), upgrading to `Large` if it does not
+    /// fit in `u64`.
+    ///
+    /// Returns `Overflow` only if it does not fit in `u128` either.
+    pub fn mediant(&self, other: &SafeFraction) -> Result<Self, FractionError> {
+        if let (Self::Small(a), Self::Small(b)) = (self, other) {
+            if let Ok(mediant) = a.mediant(b) {
+                return Ok(Self::Small(mediant));
+            }
+        }
+        self.to_fraction128().mediant(&other.to_fraction128()).map(Self::Large)
+    }
+}
+
+impl Mediant for SafeFraction {
+    fn mediant(&self, other: &Self) -> Result<Self, FractionError> {
+        SafeFraction::mediant(self, other)
+    }
+}
+
+impl SearchBound for SafeFraction {
+    fn from_integer(n: u64) -> Result<Self, FractionError> {
+        Fraction::new(n, 1).map(Self::Small)
+    }
+
+    fn value(&self) -> f64 {
+        SafeFraction::value(self)
+    }
+
+    fn cmp_with_real(&self, real: f64) -> Ordering {
+        match self {
+            Self::Small(frac) => frac.cmp_with_real(real),
+            Self::Large(frac) => frac.cmp_with_real(real),
+        }
+    }
+}
+
+impl From<Fraction> for SafeFraction {
+    fn from(frac: Fraction) -> Self {
+        Self::Small(frac)
+    }
+}
+
+impl From<Fraction128> for SafeFraction {
+    fn from(frac: Fraction128) -> Self {
+        Self::Large(frac)
+    }
+}
+
+/// Formats with the same layout as [`Fraction`].
+impl fmt::Display for SafeFraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Small(frac) => frac.fmt(f),
+            Self::Large(frac) => frac.fmt(f),
+        }
+    }
+}
+
+/// Runs the [`farey`](crate::farey) search with [`SafeFraction`] bounds.
+///
+/// Where `farey` fails with `Overflow`, this switches to `u128` components and
+/// carries on; otherwise the two visit the same mediants and return the same
+/// fraction. It never overflows: the search stops once within epsilon of
+/// the target, which happens before denominators pass 2^64, and inputs are
+/// below 2^64, so numerators stay below 2^128.
+pub fn farey_safe(real_number: f64) -> Result<SafeFraction, FractionError> {
+    for step in Bisection::<SafeFraction>::with_bounds(real_number)? {
+        let step = step?;
+        if step.converged {
+            return Ok(step.mediant);
+        }
+    }
+    unreachable!("bisection only ends after converging or failing")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pi_stays_small_past_355_113() {
+        let small = |frac: SafeFraction| match frac {
+            SafeFraction::Small(frac) => Some(frac.reduce()),
+            SafeFraction::Large(_) => None,
+        };
+        let path = Bisection::<SafeFraction>::with_bounds(std::f64::consts::PI)
+            .unwrap()
+            .map(|step| small(step.unwrap().mediant).expect("pi never needs u128"))
+            .collect::<Vec<_>>();
+        assert!(path.contains(&Fraction::new(355, 113).unwrap()));
+        assert!(path.contains(&Fraction::new(104348, 33215).unwrap()));
+        let result = farey_safe(std::f64::consts::PI).unwrap();
+        assert_eq!(small(result), Some(crate::farey(std::f64::consts::PI).unwrap()));
+    }
+
+    #[test]
+    fn huge_inputs_widen_instead_of_overflowing() {
+        assert_eq!(crate::farey(1.8e19), Err(FractionError::Overflow));
+        let result = farey_safe(1.8e19).unwrap();
+        assert_eq!(result, SafeFraction::Large(Fraction128::new(36_000_000_000_000_000_000, 2).unwrap()));
+        assert_eq!(result.value(), 1.8e19);
+        assert_eq!(farey_safe(-1.0), Err(FractionError::InvalidInput));
+    }
+}
@@ -0,0 +1,526 @@
+//! Traversals of the Stern-Brocot tree.
+//!
+//! Every positive rational appears exactly once in the tree. The node between
+//! bounds lo and hi is their mediant; its left subtree lies between lo and the
+//! mediant and its right subtree between the mediant and hi. The whole tree
+//! hangs between 0/1 and 1/0.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter;
+
+use crate::number_theory::mod_inverse;
+use crate::{farey_with_steps, ContinuedFraction, Fraction, FractionError, ParseFractionError};
+
+/// A turn taken while descending the Stern-Brocot tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Toward smaller fractions.
+    Left,
+    /// Toward larger fractions.
+    Right,
+}
+
+/// Formats as `L` or `R`.
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Direction::Left => write!(f, "L"),
+            Direction::Right => write!(f, "R"),
+        }
+    }
+}
+
+/// The longest path [`stern_brocot_path`] and [`farey_path`] return, 16 MiB
+/// of turns.
+///
+/// A path has one turn per unit of each partial quotient, so a large integer
+/// part such as u64::MAX/1 would otherwise need exabytes.
+pub const MAX_PATH_TURNS: u64 = 1 << 24;
+
+/// Returns the turns from the root 1/1 down to `frac`.
+///
+/// If p/q = [a0; a1, ..., an] then the path is R^a0 L^a1 R^a2 ... with the
+/// final run shortened by one, since the last turn lands on p/q itself. The
+/// root 1/1 has the empty path. Returns `InvalidInput` for zero, which is not
+/// in the tree, and `Overflow` for a path longer than [`MAX_PATH_TURNS`],
+/// which is checked before the path is built.
+pub fn stern_brocot_path(frac: Fraction) -> Result<Vec<Direction>, FractionError> {
+    if frac.stern_brocot_path_length()? > MAX_PATH_TURNS {
+        return Err(FractionError::Overflow);
+    }
+    let cf = ContinuedFraction::from_fraction(frac);
+    let last = cf.terms().len() - 1;
+
+    let mut path = Vec::new();
+    for (i, &term) in cf.terms().iter().enumerate() {
+        let direction = if i % 2 == 0 { Direction::Right } else { Direction::Left };
+        let run = if i == last { term - 1 } else { term };
+        path.extend(iter::repeat_n(direction, run as usize));
+    }
+    Ok(path)
+}
+
+/// Runs the [`farey`](crate::farey) search and returns its turns from the root.
+///
+/// The search starts between floor(x) and ceil(x), whose first mediant sits
+/// partway down the tree, so the path to that node is prepended; after that
+/// each bisection that moves the right bound is a `Left` turn and each that
+/// moves the left bound is a `Right` turn. The result equals
+/// `stern_brocot_path(farey(real)?)`, without walking the path twice.
+///
+/// Zero is not in the tree, but its search ends at once at 0/2 without a
+/// single turn, so its path is empty rather than an error. Returns `Overflow`
+/// for a path longer than [`MAX_PATH_TURNS`].
+pub fn farey_path(real: f64) -> Result<Vec<Direction>, FractionError> {
+    let mut start = None;
+    let mut steps = 0u64;
+    let mut turns = Vec::new();
+    farey_with_steps(real, |_, mediant, _| {
+        start.get_or_insert(*mediant);
+        steps += 1;
+        // Past the cap the path is refused anyway, so stop storing turns
+        if steps <= MAX_PATH_TURNS + 1 {
+            let above = mediant.cmp_with_real(real) == Ordering::Greater;
+            turns.push(if above { Direction::Left } else { Direction::Right });
+        }
+    })?;
+    // The last mediant is the destination, not a turn
+    turns.pop();
+
+    let start = start.expect("farey visits at least one mediant");
+    if start.numerator() == 0 {
+        return Ok(Vec::new());
+    }
+    if start.stern_brocot_path_length()?.saturating_add(steps - 1) > MAX_PATH_TURNS {
+        return Err(FractionError::Overflow);
+    }
+    let mut path = stern_brocot_path(start)?;
+    path.append(&mut turns);
+    Ok(path)
+}
+
+/// Runs [`farey_path`] and writes the turns as a string such as `"RRRLLLLLL"`
+/// (the path to 22/7).
+///
+/// Every real number that approximates to the same fraction gets the same
+/// string, which makes it a compact name for the result. Returns `Overflow`
+/// for a path longer than [`MAX_PATH_TURNS`], before any of it is written.
+pub fn mediant_path_to_target(real: f64) -> Result<String, FractionError> {
+    Ok(farey_path(real)?.iter().map(ToString::to_string).collect())
+}
+
+/// Follows a path of `L` and `R` turns from the root 1/1 and returns the node
+/// it ends at, the inverse of [`mediant_path_to_target`] on its results.
+///
+/// The empty path is 1/1. Returns a [`ParseFractionError`] (as
+/// `FractionError::Parse`) for any character other than `L` and `R`, and
+/// `Overflow` if a node does not fit in `u64`.
+pub fn mediant_path_decode(path: &str) -> Result<Fraction, FractionError> {
+    // Bounds as raw pairs, since the right bound starts at 1/0
+    let (mut lo, mut hi) = ((0u64, 1u64), (1u64, 0u64));
+    let mut node = (1u64, 1u64);
+    for c in path.chars() {
+        match c {
+            'L' => hi = node,
+            'R' => lo = node,
+            _ => return Err(ParseFractionError::InvalidCharacter(c).into()),
+        }
+        node = lo.0
+            .checked_add(hi.0)
+            .zip(lo.1.checked_add(hi.1))
+            .ok_or(FractionError::Overflow)?;
+    }
+    Fraction::new(node.0, node.1)
+}
+
+/// The longest path [`display_stern_brocot_path`] draws, about a megabyte
+/// of output.
+pub const MAX_DRAWN_TURNS: u64 = 1000;
+
+/// Draws the descent from the root 1/1 to `frac` as ASCII art, one line per
+/// node with the bounds it was the mediant of:
+///
+/// ```text
+/// 1/1 in (0/1, 1/0)
+/// `-L-> 1/2 in (0/1, 1/1)
+///   `-R-> 2/3 in (1/2, 1/1)
+/// ```
+///
+/// Each turn is indented one step further, like a branch of the tree, so the
+/// drawing grows with the square of the path length; use
+/// [`stern_brocot_path`] for long paths. `frac` is reduced first. Returns
+/// `InvalidInput` for zero and `Overflow` for a path longer than
+/// [`MAX_DRAWN_TURNS`] turns, which is checked before anything is drawn.
+pub fn display_stern_brocot_path(frac: Fraction) -> Result<String, FractionError> {
+    if frac.stern_brocot_path_length()? > MAX_DRAWN_TURNS {
+        return Err(FractionError::Overflow);
+    }
+    let path = stern_brocot_path(frac)?;
+    // Bounds as raw pairs, since the right bound starts at 1/0
+    let (mut lo, mut hi) = ((0u64, 1u64), (1u64, 0u64));
+    let mut node = (1u64, 1u64);
+    let mut out = format!("{}/{} in ({}/{}, {}/{})", node.0, node.1, lo.0, lo.1, hi.0, hi.1);
+    for (depth, direction) in path.iter().enumerate() {
+        match direction {
+            Direction::Left => hi = node,
+            Direction::Right => lo = node,
+        }
+        // Every node on the path is at most frac componentwise, so this fits
+        node = (lo.0 + hi.0, lo.1 + hi.1);
+        out.push_str(&format!(
+            "\n{:indent$}`-{direction}-> {}/{} in ({}/{}, {}/{})",
+            "",
+            node.0,
+            node.1,
+            lo.0,
+            lo.1,
+            hi.0,
+            hi.1,
+            indent = 2 * depth,
+        ));
+    }
+    Ok(out)
+}
+
+/// Returns the bounds (lo, hi) whose mediant is the node `frac`.
+///
+/// These are the nearest ancestors on either side: lo is the last node the
+/// path turned right at and hi the last it turned left at. They satisfy
+/// lo + hi = p/q componentwise and hi.p * lo.q - lo.p * hi.q = 1, so lo.q is
+/// the inverse of p modulo q. Nodes on the right spine (the integers) have
+/// 1/0 as their upper bound, returned as `None`.
+///
+/// `frac` is reduced first. Returns `InvalidInput` for zero.
+pub fn stern_brocot_ancestors(frac: Fraction) -> Result<(Fraction, Option<Fraction>), FractionError> {
+    if frac.numerator() == 0 {
+        return Err(FractionError::InvalidInput);
+    }
+    let frac = frac.reduce();
+    let (p, q) = (frac.numerator(), frac.denominator());
+    if q == 1 {
+        return Ok((Fraction::new(p - 1, 1)?, None));
+    }
+
+    let b = mod_inverse(p, q);
+    let a = ((p as u128 * b as u128 - 1) / q as u128) as u64;
+    Ok((Fraction::new(a, b)?, Some(Fraction::new(p - a, q - b)?)))
+}
+
+/// Returns the left and right children of the node `frac`.
+///
+/// With bounds (lo, hi) from [`stern_brocot_ancestors`], the children are
+/// mediant(lo, frac) and mediant(frac, hi): 1/1 has children 1/2 and 2/1,
+/// 1/2 has 1/3 and 2/3. Returns `Overflow` if a child does not fit in `u64`.
+pub fn stern_brocot_children(frac: Fraction) -> Result<(Fraction, Fraction), FractionError> {
+    let (lo, hi) = stern_brocot_ancestors(frac)?;
+    let frac = frac.reduce();
+    let left = lo.mediant(&frac)?;
+    let right = match hi {
+        Some(hi) => frac.mediant(&hi)?,
+        // mediant with 1/0
+        None => Fraction::from_checked(frac.numerator().checked_add(1), Some(frac.denominator()))?,
+    };
+    Ok((left, right))
+}
+
+/// Encodes the Stern-Brocot path of `frac` as the bits of an integer.
+///
+/// A sentinel 1 bit is followed by one bit per turn, `Left` = 0 and
+/// `Right` = 1: 1/1 is `0b1`, 1/2 is `0b10` and 2/1 is `0b11`. This is a
+/// bijection between the positive rationals and the positive integers. (The
+/// Calkin-Wilf numbering uses the same turns read in the opposite order.)
+///
+/// Returns `InvalidInput` for zero and `Overflow` if the path is longer than
+/// 63 turns.
+pub fn stern_brocot_encode(frac: Fraction) -> Result<u64, FractionError> {
+    if frac.numerator() == 0 {
+        return Err(FractionError::InvalidInput);
+    }
+    // The path length is the sum of the partial quotients minus one; check it
+    // before materializing a path that might be enormous
+    let length = ContinuedFraction::from_fraction(frac)
+        .terms()
+        .iter()
+        .fold(0u64, |sum, &term| sum.saturating_add(term))
+        - 1;
+    if length > 63 {
+        return Err(FractionError::Overflow);
+    }
+
+    let code = stern_brocot_path(frac)?
+        .iter()
+        .fold(1u64, |code, &direction| (code << 1) | (direction == Direction::Right) as u64);
+    Ok(code)
+}
+
+/// Decodes an integer produced by [`stern_brocot_encode`].
+///
+/// Returns `InvalidInput` for zero, which has no sentinel bit.
+pub fn stern_brocot_decode(code: u64) -> Result<Fraction, FractionError> {
+    if code == 0 {
+        return Err(FractionError::InvalidInput);
+    }
+    // Bounds as raw pairs, since the right bound starts at 1/0
+    let (mut lo, mut hi) = ((0u64, 1u64), (1u64, 0u64));
+    let mut node = (1u64, 1u64);
+    for bit in (0..code.ilog2()).rev() {
+        if code >> bit & 1 == 0 {
+            hi = node;
+        } else {
+            lo = node;
+        }
+        node = (lo.0 + hi.0, lo.1 + hi.1);
+    }
+    Fraction::new(node.0, node.1)
+}
+
+/// Enumerates the positive rationals in Stern-Brocot level order: 1/1, then
+/// 1/2, 2/1, then 1/3, 2/3, 3/2, 3/1, and so on, left to right, one level of
+/// the tree at a time.
+///
+/// Every positive rational appears exactly once, in lowest terms, and all of
+/// a level's fractions come before any of the next level's. The k-th item is
+/// [`stern_brocot_decode`] of k, so no queue is kept; the iterator ends after
+/// the 63rd level, past any practical use.
+pub fn all_positive_rationals() -> impl Iterator<Item = Fraction> {
+    (1..=u64::MAX).map_while(|code| stern_brocot_decode(code).ok())
+}
+
+/// Depth-first (pre-order) traversal of the subtree between two bounds.
+///
+/// Created by [`mediant_chain`].
+#[derive(Debug, Clone)]
+pub struct MediantChain {
+    stack: Vec<(Fraction, Fraction)>,
+    max_denominator: Option<u64>,
+}
+
+/// Yields the Stern-Brocot subtree between `lo` and `hi` in depth-first order.
+///
+/// The first item is the subtree root mediant(lo, hi), followed by its whole
+/// left subtree (between lo and the mediant), then its right subtree. Without
+/// a bound the left spine is infinite, so the right subtree is only reached
+/// after [`MediantChain::max_denominator`] prunes the search; with a bound the
+/// chain visits every fraction between lo and hi up to that denominator.
+/// Branches whose mediant would overflow are pruned.
+pub fn mediant_chain(lo: Fraction, hi: Fraction) -> MediantChain {
+    MediantChain { stack: vec![(lo, hi)], max_denominator: None }
+}
+
+impl MediantChain {
+    /// Prunes every node whose denominator exceeds `n`, making the chain finite.
+    pub fn max_denominator(mut self, n: u64) -> Self {
+        self.max_denominator = Some(n);
+        self
+    }
+}
+
+impl Iterator for MediantChain {
+    type Item = Fraction;
+
+    fn next(&mut self) -> Option<Fraction> {
+        while let Some((lo, hi)) = self.stack.pop() {
+            let Ok(mediant) = lo.mediant(&hi) else {
+                continue;
+            };
+            // Denominators only grow going down, so a too-large node prunes its subtree
+            if self.max_denominator.is_some_and(|n| mediant.denominator() > n) {
+                continue;
+            }
+            // Push the right half first so the left half is explored first
+            self.stack.push((mediant, hi));
+            self.stack.push((lo, mediant));
+            return Some(mediant);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frac(numerator: u64, denominator: u64) -> Fraction {
+        Fraction::new(numerator, denominator).unwrap()
+    }
+
+    #[test]
+    fn farey_path_matches_the_path_of_farey() {
+        let samples = [
+            1.0,
+            3.0,
+            0.5,
+            0.827,
+            1.0 / 3.0,
+            22.0 / 7.0,
+            std::f64::consts::PI,
+            std::f64::consts::E,
+            std::f64::consts::SQRT_2,
+            1.0 / 1000.5,
+            123.456,
+        ];
+        for x in samples {
+            assert_eq!(farey_path(x).unwrap(), stern_brocot_path(crate::farey(x).unwrap()).unwrap(), "{x}");
+        }
+        for k in 1..200 {
+            let x = k as f64 / 37.0;
+            assert_eq!(farey_path(x).unwrap(), stern_brocot_path(crate::farey(x).unwrap()).unwrap(), "{x}");
+        }
+    }
+
+    #[test]
+    fn farey_path_of_integers() {
+        assert_eq!(farey_path(0.0), Ok(Vec::new()));
+        assert_eq!(farey_path(3.0), stern_brocot_path(frac(3, 1)));
+        assert_eq!(mediant_path_to_target(0.0).as_deref(), Ok(""));
+        assert_eq!(farey_path(-1.0), Err(FractionError::InvalidInput));
+    }
+
+    #[test]
+    fn paths_refuse_large_integer_parts() {
+        let longest = stern_brocot_path(frac(MAX_PATH_TURNS + 1, 1)).unwrap();
+        assert_eq!(longest.len() as u64, MAX_PATH_TURNS);
+        assert_eq!(stern_brocot_path(frac(MAX_PATH_TURNS + 2, 1)), Err(FractionError::Overflow));
+        assert_eq!(stern_brocot_path(frac(u64::MAX, 1)), Err(FractionError::Overflow));
+        assert_eq!(stern_brocot_path(frac(1, u64::MAX)), Err(FractionError::Overflow));
+        assert_eq!(farey_path(1e13), Err(FractionError::Overflow));
+        assert_eq!(stern_brocot_path(frac(0, 1)), Err(FractionError::InvalidInput));
+    }
+
+    #[test]
+    fn encode_decode_round_trip() {
+        assert_eq!(stern_brocot_encode(frac(1, 1)), Ok(0b1));
+        assert_eq!(stern_brocot_encode(frac(1, 2)), Ok(0b10));
+        assert_eq!(stern_brocot_encode(frac(2, 1)), Ok(0b11));
+        for code in 1..2048 {
+            let decoded = stern_brocot_decode(code).unwrap();
+            assert_eq!(stern_brocot_encode(decoded), Ok(code));
+        }
+        for (p, q) in [(22, 7), (355, 113), (1, 63), (64, 1), (13, 8)] {
+            assert_eq!(stern_brocot_decode(stern_brocot_encode(frac(p, q)).unwrap()), Ok(frac(p, q)));
+        }
+        // Unreduced fractions encode as their reduced form
+        assert_eq!(stern_brocot_encode(frac(4, 6)), stern_brocot_encode(frac(2, 3)));
+    }
+
+    #[test]
+    fn encode_limits() {
+        assert_eq!(stern_brocot_encode(frac(0, 1)), Err(FractionError::InvalidInput));
+        assert_eq!(stern_brocot_decode(0), Err(FractionError::InvalidInput));
+        assert_eq!(stern_brocot_encode(frac(1, 64)), Ok(1 << 63));
+        assert_eq!(stern_brocot_encode(frac(1, 65)), Err(FractionError::Overflow));
+    }
+
+    #[test]
+    fn encoding_differs_from_calkin_wilf() {
+        // Both number 1/3 as 4, but they read the turns in opposite orders
+        assert_eq!(stern_brocot_encode(frac(1, 3)), crate::stern_diatomic_index(frac(1, 3)));
+        assert_eq!(stern_brocot_encode(frac(2, 3)), Ok(5));
+        assert_eq!(crate::stern_diatomic_index(frac(2, 3)), Ok(6));
+    }
+
+    #[test]
+    fn children_and_ancestors() {
+        assert_eq!(stern_brocot_children(frac(1, 1)), Ok((frac(1, 2), frac(2, 1))));
+        assert_eq!(stern_brocot_children(frac(1, 2)), Ok((frac(1, 3), frac(2, 3))));
+        assert_eq!(stern_brocot_children(frac(3, 1)), Ok((frac(5, 2), frac(4, 1))));
+        assert_eq!(stern_brocot_ancestors(frac(1, 1)), Ok((frac(0, 1), None)));
+        assert_eq!(stern_brocot_ancestors(frac(2, 3)), Ok((frac(1, 2), Some(frac(1, 1)))));
+        assert_eq!(stern_brocot_ancestors(frac(4, 6)), stern_brocot_ancestors(frac(2, 3)));
+        assert_eq!(stern_brocot_children(frac(0, 1)), Err(FractionError::InvalidInput));
+    }
+
+    #[test]
+    fn children_are_one_level_down() {
+        for code in 1..512 {
+            let node = stern_brocot_decode(code).unwrap();
+            let (left, right) = stern_brocot_children(node).unwrap();
+            assert_eq!(stern_brocot_encode(left), Ok(code << 1));
+            assert_eq!(stern_brocot_encode(right), Ok(code << 1 | 1));
+            let (lo, hi) = stern_brocot_ancestors(node).unwrap();
+            assert_eq!(Fraction::cross_product(lo, node), -1);
+            if let Some(hi) = hi {
+                assert_eq!(lo.mediant(&hi), Ok(node));
+            }
+        }
+    }
+
+    #[test]
+    fn mediant_path_strings() {
+        assert_eq!(mediant_path_to_target(22.0 / 7.0).as_deref(), Ok("RRRLLLLLL"));
+        assert_eq!(mediant_path_to_target(0.75).as_deref(), Ok("LRR"));
+        assert_eq!(mediant_path_to_target(0.5).as_deref(), Ok("L"));
+        assert_eq!(mediant_path_to_target(3.0).as_deref(), Ok("RR"));
+        assert_eq!(mediant_path_to_target(1.0).as_deref(), Ok(""));
+        let pi = mediant_path_to_target(std::f64::consts::PI).unwrap();
+        assert!(pi.starts_with("RRRLLLLLLLRRRRRRRRRRRRRRRL"));
+        let result = crate::farey(std::f64::consts::PI).unwrap();
+        assert_eq!(pi.len() as u64, result.stern_brocot_path_length().unwrap());
+        assert_eq!(mediant_path_to_target(f64::NAN), Err(FractionError::InvalidInput));
+    }
+
+    #[test]
+    fn mediant_path_strings_refuse_long_paths() {
+        let longest = mediant_path_to_target(MAX_PATH_TURNS as f64 + 1.0).unwrap();
+        assert_eq!(longest.len() as u64, MAX_PATH_TURNS);
+        assert!(longest.bytes().all(|c| c == b'R'));
+        assert_eq!(mediant_path_to_target(MAX_PATH_TURNS as f64 + 2.0), Err(FractionError::Overflow));
+        assert_eq!(mediant_path_to_target(1e13), Err(FractionError::Overflow));
+        assert_eq!(mediant_path_to_target(u64::MAX as f64), Err(FractionError::Overflow));
+    }
+
+    #[test]
+    fn all_positive_rationals_by_level() {
+        let first: Vec<_> = all_positive_rationals().take(15).collect();
+        let expected = [
+            frac(1, 1),
+            frac(1, 2), frac(2, 1),
+            frac(1, 3), frac(2, 3), frac(3, 2), frac(3, 1),
+            frac(1, 4), frac(2, 5), frac(3, 5), frac(3, 4), frac(4, 3), frac(5, 3), frac(5, 2), frac(4, 1),
+        ];
+        assert_eq!(first, expected);
+        // Levels 1 through 10, each in increasing order and all in lowest terms
+        let terms: Vec<_> = all_positive_rationals().take((1 << 10) - 1).collect();
+        for level in 0..10 {
+            let row = &terms[(1 << level) - 1..(1 << (level + 1)) - 1];
+            assert!(row.iter().all(|f| f.stern_brocot_path_length() == Ok(level as u64)));
+            assert!(row.windows(2).all(|pair| Fraction::cross_product(pair[0], pair[1]) < 0));
+            assert!(row.iter().all(|f| crate::gcd(f.numerator(), f.denominator()) == 1));
+        }
+    }
+
+    #[test]
+    fn mediant_path_decode_round_trip() {
+        for x in [22.0 / 7.0, 0.75, 0.5, 1.0, 3.0, std::f64::consts::PI, std::f64::consts::E, 1.0 / 1000.5] {
+            let path = mediant_path_to_target(x).unwrap();
+            assert_eq!(mediant_path_decode(&path), Ok(crate::farey(x).unwrap().reduce()), "{x}");
+        }
+        // Every path up to eight turns names a distinct node and reads back from it
+        for len in 0..=8 {
+            for bits in 0..1u32 << len {
+                let path: String = (0..len).map(|i| if bits >> i & 1 == 0 { 'L' } else { 'R' }).collect();
+                let node = mediant_path_decode(&path).unwrap();
+                let turns: String = stern_brocot_path(node).unwrap().iter().map(ToString::to_string).collect();
+                assert_eq!(turns, path);
+            }
+        }
+        assert_eq!(mediant_path_decode(""), Ok(frac(1, 1)));
+        assert_eq!(mediant_path_decode("LX"), Err(ParseFractionError::InvalidCharacter('X').into()));
+        assert_eq!(mediant_path_decode(&"LR".repeat(50)), Err(FractionError::Overflow));
+    }
+
+    #[test]
+    fn display_path_refuses_long_paths() {
+        assert_eq!(
+            display_stern_brocot_path(frac(2, 3)).as_deref(),
+            Ok("1/1 in (0/1, 1/0)\n`-L-> 1/2 in (0/1, 1/1)\n  `-R-> 2/3 in (1/2, 1/1)")
+        );
+        let longest = display_stern_brocot_path(frac(1, MAX_DRAWN_TURNS + 1)).unwrap();
+        assert_eq!(longest.lines().count() as u64, MAX_DRAWN_TURNS + 1);
+        assert_eq!(display_stern_brocot_path(frac(1, MAX_DRAWN_TURNS + 2)), Err(FractionError::Overflow));
+        // Refused before the path is built
+        assert_eq!(display_stern_brocot_path(frac(1, 1 << 40)), Err(FractionError::Overflow));
+        assert_eq!(display_stern_brocot_path(frac(0, 1)), Err(FractionError::InvalidInput));
+    }
+}
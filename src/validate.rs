@@ -0,0 +1,64 @@
+use crate::FractionError;
+
+/// Checks and normalizes a real number before approximation.
+///
+/// - NaN and infinities are rejected with `InvalidInput`.
+/// - Negative numbers are rejected with `InvalidInput`: the search runs over
+///   the non-negative half of the Stern-Brocot tree.
+/// - Numbers of 2^64 or more are rejected with `Overflow`, since their integer
+///   part does not fit in a `u64` numerator.
+/// - `-0.0` is normalized to `0.0`.
+/// - Subnormals (nonzero but below `f64::MIN_POSITIVE`) are flushed to `0.0`.
+///   No `u64` fraction other than zero comes within epsilon of them, so this
+///   is the approximation the search would settle on anyway.
+pub fn validate_input(x: f64) -> Result<f64, FractionError> {
+    if x.is_nan() || x.is_infinite() {
+        return Err(FractionError::InvalidInput);
+    }
+    if x == 0.0 || x.is_subnormal() {
+        return Ok(0.0);
+    }
+    if x < 0.0 {
+        return Err(FractionError::InvalidInput);
+    }
+    if x >= u64::MAX as f64 {
+        return Err(FractionError::Overflow);
+    }
+    Ok(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_non_finite_and_negative_numbers() {
+        assert_eq!(validate_input(f64::NAN), Err(FractionError::InvalidInput));
+        assert_eq!(validate_input(f64::INFINITY), Err(FractionError::InvalidInput));
+        assert_eq!(validate_input(f64::NEG_INFINITY), Err(FractionError::InvalidInput));
+        assert_eq!(validate_input(-1.0), Err(FractionError::InvalidInput));
+        assert_eq!(validate_input(-f64::MIN_POSITIVE), Err(FractionError::InvalidInput));
+    }
+
+    #[test]
+    fn normalizes_zeros_and_subnormals() {
+        let zero = validate_input(-0.0).unwrap();
+        assert_eq!(zero, 0.0);
+        assert!(zero.is_sign_positive());
+        assert_eq!(validate_input(5e-324), Ok(0.0));
+        assert_eq!(validate_input(f64::MIN_POSITIVE / 2.0), Ok(0.0));
+        // Negative subnormals are flushed too, rather than rejected
+        assert_eq!(validate_input(-5e-324), Ok(0.0));
+        assert_eq!(validate_input(f64::MIN_POSITIVE), Ok(f64::MIN_POSITIVE));
+    }
+
+    #[test]
+    fn rejects_numbers_from_two_to_the_64() {
+        assert_eq!(validate_input(18446744073709551616.0), Err(FractionError::Overflow));
+        assert_eq!(validate_input(f64::MAX), Err(FractionError::Overflow));
+        // The largest f64 below 2^64
+        let below = 18446744073709549568.0;
+        assert_eq!(validate_input(below), Ok(below));
+        assert_eq!(validate_input(0.5), Ok(0.5));
+    }
+}
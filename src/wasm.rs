@@ -0,0 +1,60 @@
+//! JavaScript bindings, enabled with the `wasm` feature.
+//!
+//! The default build is an rlib only, so build the module as a cdylib and
+//! generate the JavaScript glue with the wasm-bindgen CLI:
+//!
+//! ```text
+//! cargo rustc --lib --release --target wasm32-unknown-unknown --features wasm --crate-type cdylib
+//! wasm-bindgen --target web --out-dir pkg target/wasm32-unknown-unknown/release/mediant_rs.wasm
+//! ```
+//!
+//! See `pkg/demo.html` for usage from a browser. `u64` arguments and return
+//! values cross the boundary as JavaScript `BigInt`s.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{farey, Fraction};
+
+/// Approximates `x` and returns the result as `"numerator/denominator"`.
+#[wasm_bindgen]
+pub fn farey_wasm(x: f64) -> Result<String, JsError> {
+    let approx = farey(x)?;
+    Ok(format!("{}/{}", approx.numerator(), approx.denominator()))
+}
+
+/// Returns the decimal value of `num/den`.
+#[wasm_bindgen]
+pub fn fraction_value_wasm(num: u64, den: u64) -> Result<f64, JsError> {
+    Ok(Fraction::new(num, den)?.value())
+}
+
+/// A [`Fraction`] exposed to JavaScript as a class.
+#[wasm_bindgen]
+pub struct FractionJs {
+    inner: Fraction,
+}
+
+#[wasm_bindgen]
+impl FractionJs {
+    #[wasm_bindgen(constructor)]
+    pub fn new(numerator: u64, denominator: u64) -> Result<FractionJs, JsError> {
+        Ok(Self { inner: Fraction::new(numerator, denominator)? })
+    }
+
+    /// Approximates `x`, like [`farey_wasm`] but returning a `FractionJs`.
+    pub fn approximate(x: f64) -> Result<FractionJs, JsError> {
+        Ok(Self { inner: farey(x)? })
+    }
+
+    pub fn numerator(&self) -> u64 {
+        self.inner.numerator()
+    }
+
+    pub fn denominator(&self) -> u64 {
+        self.inner.denominator()
+    }
+
+    pub fn value(&self) -> f64 {
+        self.inner.value()
+    }
+}
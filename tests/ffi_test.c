@@ -0,0 +1,43 @@
+/*
+ * Smoke test for the C bindings. Run it with
+ *
+ *     tests/ffi_test.sh
+ */
+#include <math.h>
+#include <stdint.h>
+#include <stdio.h>
+
+#include "mediant.h"
+
+static int failures = 0;
+
+static void check(int ok, const char *what) {
+    if (!ok) {
+        fprintf(stderr, "FAIL: %s\n", what);
+        failures++;
+    }
+}
+
+int main(void) {
+    uint64_t num = 0, den = 0;
+
+    check(farey_approx(0.75, &num, &den) == 0, "farey_approx(0.75) succeeds");
+    check(num == 3 && den == 4, "farey_approx(0.75) == 3/4");
+
+    check(farey_approx(0.827, &num, &den) == 0, "farey_approx(0.827) succeeds");
+    check(num == 827 && den == 1000, "farey_approx(0.827) == 827/1000");
+
+    check(farey_approx(0.5, NULL, &den) == 1, "farey_approx rejects null output");
+
+    check(fraction_mediant(1, 2, 1, 3, &num, &den) == 0, "fraction_mediant succeeds");
+    check(num == 2 && den == 5, "mediant of 1/2 and 1/3 is 2/5");
+    check(fraction_mediant(1, 0, 1, 3, &num, &den) == 1, "fraction_mediant rejects zero denominator");
+
+    check(fraction_value(1, 4) == 0.25, "fraction_value(1, 4) == 0.25");
+    check(isnan(fraction_value(1, 0)), "fraction_value(1, 0) is NaN");
+
+    if (failures == 0) {
+        printf("ffi_test: ok\n");
+    }
+    return failures == 0 ? 0 : 1;
+}
@@ -0,0 +1,12 @@
+#!/bin/sh
+# Builds the C bindings as a cdylib and runs tests/ffi_test.c against them.
+set -eu
+cd "$(dirname "$0")/.."
+target="${CARGO_TARGET_DIR:-target}"
+
+cargo rustc --lib --features c-ffi --crate-type cdylib
+# build.rs writes the header to its OUT_DIR; take the most recent one
+header_dir=$(dirname "$(ls -t "$target"/debug/build/mediant-rs-*/out/mediant.h | head -n 1)")
+
+cc tests/ffi_test.c -I"$header_dir" -L"$target/debug" -lmediant_rs -lm -o "$target/ffi_test"
+LD_LIBRARY_PATH="$target/debug" "$target/ffi_test"